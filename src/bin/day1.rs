@@ -1,32 +1,52 @@
-use std::io;
+use spraints_advent_of_code_2019::fuel;
+use std::env;
+use std::io::{self, BufRead};
+use std::process;
 
 fn main() {
-    println!("Starting Fuel Counter-Upper!");
-
-    let mut fuel = 0;
-
-    loop {
-        let mut line = String::new();
-        let bytes = io::stdin()
-            .read_line(&mut line)
-            .expect("Error reading from stdin");
-        if bytes == 0 {
-            break;
-        }
-        match line.trim().parse() {
-            Err(error) => println!("error parsing {}: {}", line.trim(), error),
-            Ok(weight) => fuel += calc_fuel(weight),
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let mut verbose = false;
+    let mut csv = false;
+    for arg in args {
+        match arg {
+            "-v" => verbose = true,
+            "--csv" => csv = true,
+            _ => {
+                eprintln!("Usage: day1 [-v] [--csv] < MANIFEST");
+                process::exit(1);
+            }
         }
     }
 
-    println!("got it: {}", fuel);
-}
+    let lines = io::stdin()
+        .lock()
+        .lines()
+        .map(|line| line.expect("Error reading from stdin"));
+    let report = fuel::calculate(lines);
 
-fn calc_fuel(weight: i32) -> i32 {
-    let fuel = weight / 3 - 2;
-    if fuel <= 0 {
-        0
-    } else {
-        fuel + calc_fuel(fuel)
+    for error in &report.errors {
+        eprintln!("error: {}", error);
+    }
+    if csv {
+        print!("{}", report.to_csv());
+        return;
     }
+
+    println!("Starting Fuel Counter-Upper!");
+    if verbose {
+        for module in &report.modules {
+            println!(
+                "line {}: {} mass {} needs {} ({} with fuel for fuel)",
+                module.line,
+                module.name.as_deref().unwrap_or("module"),
+                module.mass,
+                module.simple,
+                module.recursive
+            );
+        }
+    }
+
+    println!("FUEL FOR MODULES: {}", report.simple);
+    println!("FUEL INCLUDING FUEL: {}", report.recursive);
 }
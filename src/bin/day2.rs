@@ -1,3 +1,5 @@
+use spraints_advent_of_code_2019::intcode::symbolic::{self, Cell, Solution};
+use spraints_advent_of_code_2019::intcode::{IntCodeMemory, Item};
 use std::io;
 
 fn main() {
@@ -21,13 +23,16 @@ fn main() {
 
     tryrun(&memory, 12, 2, 2);
 
-    for one in 1..99 {
-        for two in 1..99 {
-            let res = tryrun(&memory, one, two, 0);
-            if res == 19690720 {
-                tryrun(&memory, one, two, 1);
-            }
+    let program: IntCodeMemory = memory.iter().map(|n| *n as Item).collect();
+    let unknowns = vec![
+        symbolic::unknown("noun", Cell::Memory(1), 0..=99),
+        symbolic::unknown("verb", Cell::Memory(2), 0..=99),
+    ];
+    match symbolic::solve(&program, &unknowns, 0, 19690720) {
+        Solution::Linear(vals) | Solution::Search(vals) => {
+            tryrun(&memory, vals[0] as usize, vals[1] as usize, 1);
         }
+        Solution::NotFound => println!("NO NOUN AND VERB PRODUCE 19690720"),
     }
 }
 
@@ -52,6 +57,7 @@ mod tests {
     }
 }
 
+#[allow(clippy::ptr_arg)]
 fn tryrun(memory: &Vec<usize>, one: usize, two: usize, verbose_level: u8) -> usize {
     let mut runmem = memory.to_vec();
     runmem[1] = one;
@@ -75,6 +81,7 @@ fn tryrun(memory: &Vec<usize>, one: usize, two: usize, verbose_level: u8) -> usi
     }
 }
 
+#[allow(clippy::needless_borrow)]
 fn run(mut memory: &mut Vec<usize>, verbose_level: u8) {
     let opcodes = [op_add, op_mult];
 
@@ -97,6 +104,7 @@ fn run(mut memory: &mut Vec<usize>, verbose_level: u8) {
     }
 }
 
+#[allow(clippy::ptr_arg)]
 fn op_add(memory: &mut Vec<usize>, arg1: usize, arg2: usize, arg3: usize, verbose_level: u8) {
     if verbose_level > 1 {
         println!("  ADD {} + {} => {}", arg1, arg2, arg3);
@@ -104,6 +112,7 @@ fn op_add(memory: &mut Vec<usize>, arg1: usize, arg2: usize, arg3: usize, verbos
     memory[arg3] = memory[arg1] + memory[arg2];
 }
 
+#[allow(clippy::ptr_arg)]
 fn op_mult(memory: &mut Vec<usize>, arg1: usize, arg2: usize, arg3: usize, verbose_level: u8) {
     if verbose_level > 1 {
         println!("  MULT {} * {} => {}", arg1, arg2, arg3);
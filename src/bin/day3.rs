@@ -1,24 +1,127 @@
-use std::collections::HashMap;
-use std::io;
-
-type Segment = (char, u32);
-type Path = Vec<Segment>;
-type Coord = (i32, i32);
-type WalkState = (Coord, u32);
-type Location = (u8, u32, u32); // names, manhattan distance, cumulative wire length.
-type Grid = HashMap<Coord, Location>;
+use spraints_advent_of_code_2019::wires::{self, render, Path, Wire};
+use std::env;
+use std::fs;
+use std::io::{self, BufRead};
+use std::process;
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_path, plot, taxi_dist, wire_dist, HashMap};
+    use super::wires;
+    use std::collections::HashMap;
 
-    fn test_path(line1: &str, line2: &str, expected: (u32, u32)) {
+    // The original solution, which visits every cell along both wires. The
+    // tests check that the segment version gets the same answers.
+    type Segment = (char, u32);
+    type Path = Vec<Segment>;
+    type Coord = (i32, i32);
+    type WalkState = (Coord, u32);
+    type Location = (u8, u32, u32); // names, manhattan distance, cumulative wire length.
+    type Grid = HashMap<Coord, Location>;
+
+    fn taxi_dist(grid: &Grid) -> u32 {
+        grid.values().fold(10000000, |res, (wires, dist, _)| {
+            if *wires == 0x03 && dist < &res {
+                *dist
+            } else {
+                res
+            }
+        })
+    }
+
+    fn wire_dist(grid: &Grid) -> u32 {
+        grid.values().fold(10000000, |res, (wires, _, dist)| {
+            if *wires == 0x03 && dist < &res {
+                *dist
+            } else {
+                res
+            }
+        })
+    }
+
+    #[allow(clippy::needless_borrow)]
+    fn plot(mut grid: &mut Grid, path: Path, wire_name: u8) {
+        let mut state = ((0, 0), 0);
+        for segment in path {
+            println!("{:?} --> {:?}", state, segment);
+            state = match segment {
+                ('R', n) => walk(&mut grid, state, wire_name, n, |(x, y)| (x + 1, y)),
+                ('L', n) => walk(&mut grid, state, wire_name, n, |(x, y)| (x - 1, y)),
+                ('U', n) => walk(&mut grid, state, wire_name, n, |(x, y)| (x, y + 1)),
+                ('D', n) => walk(&mut grid, state, wire_name, n, |(x, y)| (x, y - 1)),
+                other => {
+                    println!("ERR unrecognized segment for {}: {:?}", wire_name, other);
+                    state
+                }
+            };
+        }
+        println!("{:?}", state);
+    }
+
+    #[allow(clippy::assign_op_pattern)]
+    fn walk<F>(grid: &mut Grid, state: WalkState, wire_name: u8, count: u32, step: F) -> WalkState
+    where
+        F: Fn(Coord) -> Coord,
+    {
+        let (mut coords, mut dist) = state;
+        for _ in 0..count {
+            coords = step(coords);
+            dist = dist + 1;
+            let (wire_names, _, wiredist) =
+                grid.entry(coords).or_insert((0, calc_dist(&coords), 0));
+            if *wire_names & wire_name == 0 {
+                *wiredist += dist
+            }
+            *wire_names = *wire_names | wire_name;
+        }
+        (coords, dist)
+    }
+
+    fn calc_dist(coords: &Coord) -> u32 {
+        let (x, y) = coords;
+        (x.abs() + y.abs()) as u32
+    }
+
+    #[allow(clippy::redundant_closure)]
+    fn parse_path(s: &str) -> Path {
+        s.split(',').map(|s| parse_segment(s)).collect()
+    }
+
+    #[allow(clippy::iter_nth_zero)]
+    fn parse_segment(s: &str) -> Segment {
+        let direction = s.chars().nth(0).unwrap();
+        let distance = match s.get(1..) {
+            Some(part) => part.parse().expect("COULD NOT PARSE"),
+            None => 0,
+        };
+        (direction, distance)
+    }
+
+    // The original answers for two wires, or None if they don't cross.
+    fn original(line1: &str, line2: &str) -> Option<(u32, u32)> {
         let mut grid = HashMap::new();
         plot(&mut grid, parse_path(line1), 0x01);
         plot(&mut grid, parse_path(line2), 0x02);
+        match (taxi_dist(&grid), wire_dist(&grid)) {
+            (10000000, _) => None,
+            answers => Some(answers),
+        }
+    }
 
-        assert_eq!(expected.0, taxi_dist(&grid));
-        assert_eq!(expected.1, wire_dist(&grid));
+    // The segment version's answers for cells crossed by at least `k` wires.
+    fn answers(lines: &[&str], k: usize) -> Option<(u32, u32)> {
+        let wires: Vec<_> = lines
+            .iter()
+            .map(|line| super::plot(wires::parse_path(line).unwrap(), false))
+            .collect();
+        let crossings = wires::crossings(&wires);
+        let taxi = wires::closest(&crossings, k)?;
+        let wire = wires::shortest(&crossings, k)?;
+        Some((taxi as u32, wire as u32))
+    }
+
+    fn test_path(line1: &str, line2: &str, expected: (u32, u32)) {
+        assert_eq!(Some(expected), original(line1, line2));
+        assert_eq!(Some(expected), answers(&[line1, line2], 2));
     }
 
     #[test]
@@ -38,97 +141,129 @@ mod tests {
             (135, 410),
         );
     }
-}
-
-fn main() {
-    println!("WIRE DETANGLER");
-
-    let mut grid = HashMap::new();
 
-    plot(&mut grid, read_path(), 0x01);
-    plot(&mut grid, read_path(), 0x02);
-
-    println!("MIN TAXI DISTANCE: {}", taxi_dist(&grid));
-    println!("MIN WIRE DISTANCE: {}", wire_dist(&grid));
-}
+    #[test]
+    fn test_many_wires() {
+        let lines = [
+            "R75,D30,R83,U83,L12,D49,R71,U7,L72",
+            "U62,R66,U55,R34,D71,R55,D58,R83",
+            "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+            "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+            "R8,U5,L5,D3",
+            "U7,R6,D4,L4",
+        ];
 
-fn taxi_dist(grid: &Grid) -> u32 {
-    grid.values().fold(10000000, |res, (wires, dist, _)| {
-        if *wires == 0x03 && dist < &res {
-            *dist
-        } else {
-            res
+        // The closest crossing of any two wires is the best of every pair.
+        let mut pairs = vec![];
+        for (i, line1) in lines.iter().enumerate() {
+            for line2 in &lines[i + 1..] {
+                pairs.extend(original(line1, line2));
+            }
         }
-    })
-}
+        let taxi = pairs.iter().map(|answers| answers.0).min();
+        let wire = pairs.iter().map(|answers| answers.1).min();
+        assert_eq!(taxi.zip(wire), answers(&lines, 2));
 
-fn wire_dist(grid: &Grid) -> u32 {
-    grid.values().fold(10000000, |res, (wires, _, dist)| {
-        if *wires == 0x03 && dist < &res {
-            *dist
-        } else {
-            res
+        // Three of them start out going right, so they all pass (1, 0).
+        assert_eq!(Some((1, 3)), answers(&lines, 3));
+        for k in 4..=lines.len() {
+            assert_eq!(None, answers(&lines, k));
         }
-    })
+        assert_eq!(None, answers(&lines[..1], 2));
+    }
 }
 
-fn plot(mut grid: &mut Grid, path: Path, wire_name: u8) {
-    let mut state = ((0, 0), 0);
-    for segment in path {
-        println!("{:?} --> {:?}", state, segment);
-        state = match segment {
-            ('R', n) => walk(&mut grid, state, wire_name, n, |(x, y)| (x + 1, y)),
-            ('L', n) => walk(&mut grid, state, wire_name, n, |(x, y)| (x - 1, y)),
-            ('U', n) => walk(&mut grid, state, wire_name, n, |(x, y)| (x, y + 1)),
-            ('D', n) => walk(&mut grid, state, wire_name, n, |(x, y)| (x, y - 1)),
-            other => {
-                println!("ERR unrecognized segment for {}: {:?}", wire_name, other);
-                state
-            }
-        };
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let mut verbose = false;
+    let mut svg = None;
+    let mut ascii = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match *arg {
+            "-v" => verbose = true,
+            "--svg" => svg = Some(*args.next().unwrap_or_else(|| usage())),
+            "--ascii" => ascii = true,
+            _ => usage(),
+        }
     }
-    println!("{:?}", state);
-}
 
-fn walk<F>(grid: &mut Grid, state: WalkState, wire_name: u8, count: u32, step: F) -> WalkState
-where
-    F: Fn(Coord) -> Coord,
-{
-    let (mut coords, mut dist) = state;
-    for _ in 0..count {
-        coords = step(coords);
-        dist = dist + 1;
-        let (wire_names, _, wiredist) = grid.entry(coords).or_insert((0, calc_dist(&coords), 0));
-        if *wire_names & wire_name == 0 {
-            *wiredist += dist
+    println!("WIRE DETANGLER");
+
+    let wires: Vec<Wire> = read_paths()
+        .into_iter()
+        .map(|path| plot(path, verbose))
+        .collect();
+    let crossings = wires::crossings(&wires);
+    println!("CROSSINGS: {}", crossings.len());
+
+    // One wire, or wires that never meet, have no answer.
+    match (
+        wires::closest(&crossings, 2),
+        wires::shortest(&crossings, 2),
+    ) {
+        (Some(taxi), Some(wire)) => {
+            println!("MIN TAXI DISTANCE: {}", taxi);
+            println!("MIN WIRE DISTANCE: {}", wire);
+        }
+        _ => println!("NO CROSSINGS"),
+    }
+    if let Some(out) = svg {
+        fs::write(out, render::svg(&wires, &crossings)).expect("Error writing SVG");
+    }
+    if ascii {
+        match render::ascii(&wires, &crossings) {
+            Some(picture) => print!("{}", picture),
+            None => println!("TOO BIG TO DRAW, TRY --svg"),
         }
-        *wire_names = *wire_names | wire_name;
     }
-    (coords, dist)
-}
 
-fn calc_dist(coords: &Coord) -> u32 {
-    let (x, y) = coords;
-    (x.abs() + y.abs()) as u32
+    for k in 3..=wires.len() {
+        if let (Some(taxi), Some(wire)) = (
+            wires::closest(&crossings, k),
+            wires::shortest(&crossings, k),
+        ) {
+            println!("MIN TAXI DISTANCE FOR {} WIRES: {}", k, taxi);
+            println!("MIN WIRE DISTANCE FOR {} WIRES: {}", k, wire);
+        }
+    }
 }
 
-fn read_path() -> Path {
-    let mut line = String::new();
-    io::stdin()
-        .read_line(&mut line)
-        .expect("Error reading STDIN");
-    parse_path(line.trim())
+fn usage() -> ! {
+    eprintln!("Usage: day3 [-v] [--svg OUT] [--ascii] < WIRES");
+    process::exit(1);
 }
 
-fn parse_path(s: &str) -> Path {
-    s.split(',').map(|s| parse_segment(s)).collect()
+fn plot(path: Path, verbose: bool) -> Wire {
+    let mut wire = Wire::default();
+    for segment in path {
+        if verbose {
+            println!("{:?} --> {:?}", (wire.end(), wire.length()), segment);
+        }
+        wire.extend(segment.0, segment.1 as i64);
+    }
+    if verbose {
+        println!("{:?}", (wire.end(), wire.length()));
+    }
+    wire
 }
 
-fn parse_segment(s: &str) -> Segment {
-    let direction = s.chars().nth(0).unwrap();
-    let distance = match s.get(1..) {
-        Some(part) => part.parse().expect("COULD NOT PARSE"),
-        None => 0,
-    };
-    (direction, distance)
+// One path per line, until the end of the input.
+fn read_paths() -> Vec<Path> {
+    let mut res = vec![];
+    for (i, line) in io::stdin().lock().lines().enumerate() {
+        let line = line.expect("Error reading STDIN");
+        if line.trim().is_empty() {
+            continue;
+        }
+        match wires::parse_path(&line) {
+            Ok(path) => res.push(path),
+            Err(e) => {
+                eprintln!("Error on line {}: {}", i + 1, e);
+                process::exit(1);
+            }
+        }
+    }
+    res
 }
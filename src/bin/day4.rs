@@ -1,11 +1,29 @@
+use spraints_advent_of_code_2019::password;
+use std::env;
 use std::io;
+use std::process;
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let mut verbose = false;
+    let mut brute = false;
+    for arg in args {
+        match arg {
+            "-v" => verbose = true,
+            "--brute" => brute = true,
+            _ => {
+                eprintln!("Usage: day4 [-v] [--brute] < RANGE");
+                process::exit(1);
+            }
+        }
+    }
+
     let mut line = String::new();
     io::stdin()
         .read_line(&mut line)
         .expect("Error reading stdin");
-    let parts: Vec<u32> = line
+    let parts: Vec<u64> = line
         .trim()
         .split('-')
         .map(|s| s.parse().expect("Error parsing number"))
@@ -14,29 +32,32 @@ fn main() {
     let max = parts[1];
     println!("SEARCH IN RANGE [{}:{}]", min, max);
 
-    let mut candidates = 0;
-    for i in min..max {
-        if is_candidate(i) {
-            candidates += 1;
-            println!("[{}] {}", candidates, i);
+    let rules = password::puzzle_rules();
+    if verbose {
+        for i in min..=max {
+            let names: Vec<&str> = rules
+                .iter()
+                .filter(|set| set.is_candidate(i))
+                .map(|set| set.name.as_str())
+                .collect();
+            if !names.is_empty() {
+                println!("{} {:?}", i, names);
+            }
         }
     }
-}
 
-fn is_candidate(num: u32) -> bool {
-    let d1 = num / 100_000;
-    let d2 = (num / 10_000) % 10;
-    let d3 = (num / 1_000) % 10;
-    let d4 = (num / 100) % 10;
-    let d5 = (num / 10) % 10;
-    let d6 = num % 10;
-
-    let increases_only = d1 <= d2 && d2 <= d3 && d3 <= d4 && d4 <= d5 && d5 <= d6;
-    let has_double = (d1 == d2 && d2 != d3)
-        || (d1 != d2 && d2 == d3 && d3 != d4)
-        || (d2 != d3 && d3 == d4 && d4 != d5)
-        || (d3 != d4 && d4 == d5 && d5 != d6)
-        || (d4 != d5 && d5 == d6);
-
-    increases_only && has_double
+    // The fast counter only knows the puzzle's rules. --brute checks every
+    // number against the rule sets instead.
+    let counts = if brute {
+        password::count(min..=max, &rules)
+            .into_iter()
+            .map(|n| n as u64)
+            .collect()
+    } else {
+        let counts = password::count_fast(min..=max);
+        vec![counts.pair, counts.exact_pair]
+    };
+    for (set, count) in rules.iter().zip(counts) {
+        println!("{}: {}", set.name, count);
+    }
 }
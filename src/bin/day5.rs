@@ -21,6 +21,7 @@ fn main() {
     run(&mut memory.clone());
 }
 
+#[allow(clippy::needless_borrow)]
 fn run(mut memory: &mut Memory) {
     let opcodes = [
         op_add,
@@ -64,6 +65,7 @@ enum ModeType {
 
 impl Iterator for ModesIter {
     type Item = ModeType;
+    #[allow(clippy::assign_op_pattern)]
     fn next(&mut self) -> Option<Self::Item> {
         let cur = self.modes % 10;
         self.modes = self.modes / 10;
@@ -75,6 +77,7 @@ impl Iterator for ModesIter {
     }
 }
 
+#[allow(clippy::into_iter_on_ref)]
 fn get_params(memory: &Memory, modes: ModesIter, pc: usize, count: usize) -> Memory {
     let params: Memory = memory[pc + 1..pc + 1 + count]
         .into_iter()
@@ -88,6 +91,7 @@ fn get_params(memory: &Memory, modes: ModesIter, pc: usize, count: usize) -> Mem
     params
 }
 
+#[allow(clippy::needless_borrow)]
 fn op_add(memory: &mut Memory, modes: ModesIter, pc: usize) -> usize {
     let params = get_params(&memory, modes, pc, 2);
     let arg1 = params[0];
@@ -97,6 +101,7 @@ fn op_add(memory: &mut Memory, modes: ModesIter, pc: usize) -> usize {
     pc + 4
 }
 
+#[allow(clippy::needless_borrow)]
 fn op_mult(memory: &mut Memory, modes: ModesIter, pc: usize) -> usize {
     let params = get_params(&memory, modes, pc, 2);
     let arg1 = params[0];
@@ -116,6 +121,7 @@ fn op_input(memory: &mut Memory, _: ModesIter, pc: usize) -> usize {
     pc + 2
 }
 
+#[allow(clippy::needless_borrow)]
 fn op_output(memory: &mut Memory, modes: ModesIter, pc: usize) -> usize {
     let params = get_params(&memory, modes, pc, 1);
     println!(" ==> {}", params[0]);
@@ -130,6 +136,7 @@ fn op_jump_if_false(memory: &mut Memory, modes: ModesIter, pc: usize) -> usize {
     jump_if(false, memory, modes, pc)
 }
 
+#[allow(clippy::needless_borrow)]
 fn jump_if(cond: bool, memory: &mut Memory, modes: ModesIter, pc: usize) -> usize {
     let params = get_params(&memory, modes, pc, 2);
     let arg1 = params[0];
@@ -141,6 +148,7 @@ fn jump_if(cond: bool, memory: &mut Memory, modes: ModesIter, pc: usize) -> usiz
     }
 }
 
+#[allow(clippy::needless_borrow)]
 fn op_lt(memory: &mut Memory, modes: ModesIter, pc: usize) -> usize {
     let params = get_params(&memory, modes, pc, 2);
     let arg1 = params[0];
@@ -150,6 +158,7 @@ fn op_lt(memory: &mut Memory, modes: ModesIter, pc: usize) -> usize {
     pc + 4
 }
 
+#[allow(clippy::needless_borrow)]
 fn op_eq(memory: &mut Memory, modes: ModesIter, pc: usize) -> usize {
     let params = get_params(&memory, modes, pc, 2);
     let arg1 = params[0];
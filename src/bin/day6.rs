@@ -1,153 +1,134 @@
-use std::collections::HashMap;
-use std::io::{self, BufRead};
-
-#[derive(Debug)]
-struct Orbit {
-    center: String,
-    satellite: String,
-}
-
-type Orbits = Vec<Orbit>;
-
-struct OrbObj {
-    center: Option<String>,
-    satellites: Vec<String>,
-}
-
-type OrbitGraph = HashMap<String, OrbObj>;
+use spraints_advent_of_code_2019::orbit::{self, export, OrbitMap};
+use std::env;
+use std::io::{self, BufRead, Read};
+use std::process;
 
 fn main() {
-    let orbits = read_orbits();
-    //println!("{:?}", orbits);
-    let orbits = make_graph(orbits);
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
 
-    println!("TOTAL ORBITS: {}", walk_dist(&orbits, "COM"));
-
-    let path = find_path(&orbits, "YOU", "SAN").unwrap();
-    println!("DISTANCE FROM 'YOU' TO 'SAN': {}", path.len() - 1);
-    println!("   {:?}", path);
-}
-
-fn find_path(objs: &OrbitGraph, from: &str, to: &str) -> Result<Vec<String>, String> {
-    let from = from.to_string();
-    let to = to.to_string();
-    match objs.get(&from) {
-        None => Err("FROM not found".to_string()),
-        Some(from) => match objs.get(&to) {
-            None => Err("TO not found".to_string()),
-            Some(to) => intersecting_paths(
-                walk_to_root(objs, &from.center, vec![]),
-                walk_to_root(objs, &to.center, vec![]),
-            ),
-        },
+    let (json, args) = match args.as_slice() {
+        ["--json", rest @ ..] => (true, rest),
+        rest => (false, rest),
+    };
+    if !matches!(
+        args,
+        [] | ["stats"]
+            | ["lca", _, _]
+            | ["distance", _, _]
+            | ["path", _, _]
+            | ["dot"]
+            | ["dot", _, _]
+            | ["json"]
+            | ["json", "--flat"]
+    ) {
+        usage();
     }
-}
 
-fn walk_to_root(objs: &OrbitGraph, start: &Option<String>, mut accum: Vec<String>) -> Vec<String> {
-    match start {
-        None => accum,
-        Some(next_name) => {
-            accum.push(next_name.to_string());
-            match objs.get(next_name) {
-                None => accum,
-                Some(next_obj) => walk_to_root(objs, &next_obj.center, accum),
+    let orbits = if json {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .expect("Error reading from stdin");
+        export::from_flat_json(&text)
+    } else {
+        let lines = io::stdin()
+            .lock()
+            .lines()
+            .map(|line| line.expect("Error reading from stdin"));
+        orbit::parse(lines)
+    };
+    let orbits = match orbits {
+        Ok(orbits) => orbits,
+        Err(errors) => {
+            for error in errors {
+                eprintln!("error: {}", error);
             }
+            process::exit(1);
         }
-    }
-}
+    };
 
-fn intersecting_paths(path1: Vec<String>, path2: Vec<String>) -> Result<Vec<String>, String> {
-    println!("p1: {:?}", path1);
-    println!("p2: {:?}", path2);
-    let overlap = path1
-        .iter()
-        .rev()
-        .zip(path2.iter().rev())
-        .filter(|(item1, item2)| item1 == item2)
-        .count();
-    if overlap == 0 {
-        Err("no overlap!".to_string())
-    } else {
-        let path1 = path1.iter().rev().skip(overlap - 1);
-        let path2 = path2.iter().rev().skip(overlap);
-        Ok(path1.rev().chain(path2).map(|s| s.to_string()).collect())
+    match args {
+        ["stats"] => stats(&orbits),
+        ["lca", a, b] => println!("{}", query(&orbits, a, b, OrbitMap::lca)),
+        ["distance", a, b] => println!("{}", query(&orbits, a, b, OrbitMap::distance)),
+        ["path", a, b] => println!("{}", query(&orbits, a, b, OrbitMap::path).join(" -> ")),
+        ["dot"] => print!("{}", export::to_dot(&orbits, &[])),
+        ["dot", a, b] => print!(
+            "{}",
+            export::to_dot(&orbits, &query(&orbits, a, b, OrbitMap::path))
+        ),
+        ["json"] => print!("{}", export::to_json(&orbits)),
+        ["json", "--flat"] => print!("{}", export::to_flat_json(&orbits)),
+        _ => puzzle(&orbits),
     }
 }
 
-fn walk_dist(objs: &OrbitGraph, center: &str) -> u32 {
-    walk_dist2(objs, &center.to_string()).1
+fn usage() -> ! {
+    eprintln!("Usage: day6 [--json] [COMMAND] < MAP");
+    eprintln!("With --json, the map is a JSON object like {{\"B\": \"COM\"}} that has each");
+    eprintln!("object's center. With no command, prints the answers to the puzzle.");
+    eprintln!("Commands:");
+    eprintln!("  stats         count orbits and objects at each depth, and show the");
+    eprintln!("                biggest subtrees");
+    eprintln!("  lca A B       the deepest object that A and B both are or orbit");
+    eprintln!("  distance A B  how many orbits apart A and B are");
+    eprintln!("  path A B      the objects from A to B");
+    eprintln!("  dot [A B]     print the map as Graphviz DOT, with the path from A to B");
+    eprintln!("                in red (try YOU SAN)");
+    eprintln!("  json [--flat] print the map as nested JSON, or as flat JSON that");
+    eprintln!("                --json can read");
+    process::exit(1);
 }
 
-// Returns (num_descendants, orbits)
-fn walk_dist2(objs: &OrbitGraph, center: &String) -> (u32, u32) {
-    match objs.get(center) {
-        None => {
-            println!("WARNING! tried to walk to {}, but it isn't found!", center);
-            (0, 0)
-        }
-        Some(obj) => {
-            let mut res = (0, 0);
-            for sat in &obj.satellites {
-                let satres = walk_dist2(objs, sat);
-                // Descendents accumulates all of the satellite's descendants, plus the satellite.
-                res.0 = res.0 + satres.0 + 1;
-                // Orbits accumulates all of the satellite's orbits, plus one more for each
-                // descendant, plus one for this orbit.
-                res.1 = res.1 + satres.1 + satres.0 + 1;
-            }
-            res
+fn query<'a, T>(
+    orbits: &'a OrbitMap,
+    a: &str,
+    b: &str,
+    f: fn(&'a OrbitMap, &str, &str) -> Option<T>,
+) -> T {
+    for name in &[a, b] {
+        if !orbits.contains(name) {
+            eprintln!("error: {} isn't in the map", name);
+            process::exit(1);
         }
     }
+    f(orbits, a, b).unwrap()
 }
 
-fn read_orbits() -> Orbits {
-    let mut res = vec![];
-    for line in io::stdin().lock().lines() {
-        let parts: Vec<String> = line
-            .unwrap()
-            .trim()
-            .split(')')
-            .map(|s| s.to_string())
-            .collect();
-        res.push(Orbit {
-            center: parts[0].to_string(),
-            satellite: parts[1].to_string(),
-        });
+fn puzzle(orbits: &OrbitMap) {
+    println!("TOTAL ORBITS: {}", orbits.total_orbits());
+
+    match (orbits.center("YOU"), orbits.center("SAN")) {
+        (Some(from), Some(to)) => {
+            let path = orbits.path(from, to).unwrap();
+            println!("DISTANCE FROM 'YOU' TO 'SAN': {}", path.len() - 1);
+            println!("   {:?}", path);
+        }
+        _ => println!("DISTANCE FROM 'YOU' TO 'SAN': YOU and SAN both need to orbit something"),
     }
-    res
 }
 
-fn make_graph(orbits: Orbits) -> OrbitGraph {
-    let mut res = HashMap::new();
-    for orbit in orbits {
-        match res.get_mut(&orbit.satellite) {
-            None => {
-                res.insert(
-                    orbit.satellite.to_string(),
-                    OrbObj {
-                        center: Some(orbit.center.to_string()),
-                        satellites: vec![],
-                    },
-                );
-            }
-            Some(obj) => {
-                obj.center = Some(orbit.center.to_string());
-            }
-        };
-        match res.get_mut(&orbit.center) {
-            None => {
-                res.insert(
-                    orbit.center.to_string(),
-                    OrbObj {
-                        center: None,
-                        satellites: vec![orbit.satellite.to_string()],
-                    },
-                );
-            }
-            Some(obj) => {
-                obj.satellites.push(orbit.satellite.to_string());
-            }
-        };
+fn stats(orbits: &OrbitMap) {
+    let stats = orbits.stats();
+    println!("OBJECTS: {}", stats.objects);
+    println!("DIRECT ORBITS: {}", stats.direct);
+    println!("INDIRECT ORBITS: {}", stats.indirect);
+    println!("MAX DEPTH: {}", stats.max_depth);
+    println!("OBJECTS AT EACH DEPTH:");
+    for (depth, count) in stats.depths.iter().enumerate() {
+        println!("  {:4} {}", depth, count);
+    }
+
+    let mut sizes: Vec<(usize, &str)> = orbits
+        .subtree_sizes()
+        .into_iter()
+        .filter(|(name, _)| *name != orbits.root())
+        .map(|(name, size)| (size, name))
+        .collect();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    println!("BIGGEST SUBTREES:");
+    for (size, name) in sizes.iter().take(5) {
+        println!("  {} {}", name, size);
     }
-    res
 }
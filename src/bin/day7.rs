@@ -64,6 +64,7 @@ fn main() {
     part2(&program, false);
 }
 
+#[allow(clippy::needless_borrow)]
 fn part2(program: &IntCodeMemory, strict: bool) -> intcode::Item {
     println!("PART 2");
     println!("------");
@@ -148,6 +149,7 @@ fn try_inputs2(program: &IntCodeMemory, inputs: [intcode::Item; 5], strict: bool
     }
 }
 
+#[allow(clippy::needless_borrow)]
 fn part1(program: &IntCodeMemory, strict: bool) -> intcode::Item {
     println!("PART 1");
     println!("------");
@@ -210,6 +212,7 @@ fn try_inputs(program: &IntCodeMemory, inputs: [intcode::Item; 5], strict: bool)
     }
 }
 
+#[allow(clippy::expect_fun_call)]
 fn intcode_send(chan: &Sender<Option<intcode::Item>>, val: intcode::Item) {
     chan.send(Some(val))
         .expect(&format!("should be able to send {} to {:?}", val, chan));
@@ -238,6 +241,7 @@ fn copy_chan(label: &str, rx: Receiver<Option<intcode::Item>>, tx: Sender<Option
     //println!("{} finished", label);
 }
 
+#[allow(clippy::clone_on_copy)]
 fn all_perms(vals: [intcode::Item; 5]) -> Vec<[intcode::Item; 5]> {
     let mut res = vec![];
     let mut vals = vals.clone();
@@ -245,6 +249,7 @@ fn all_perms(vals: [intcode::Item; 5]) -> Vec<[intcode::Item; 5]> {
     res
 }
 
+#[allow(clippy::clone_on_copy)]
 fn heap_permutation(res: &mut Vec<[intcode::Item; 5]>, vals: &mut [intcode::Item; 5], size: usize) {
     if size == 1 {
         res.push(vals.clone());
@@ -1,159 +1,93 @@
-use std::io;
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_parse_image() {
-        let image = parse_image("123456789012", 3, 2);
-        assert_eq!(
-            vec![
-                vec![vec![1, 2, 3], vec![4, 5, 6]],
-                vec![vec![7, 8, 9], vec![0, 1, 2]],
-            ],
-            image
-        );
-        assert_eq!(
-            vec![
-                [0, 1, 1, 1, 1, 1, 1, 0, 0, 0],
-                [1, 1, 1, 0, 0, 0, 0, 1, 1, 1],
-            ],
-            score_image(&image)
-        );
-    }
-
-    #[test]
-    fn test_render() {
-        let image = parse_image("0222112222120000", 2, 2);
-        assert_eq!(" ■\n■ \n", render(&image));
-    }
-}
+use spraints_advent_of_code_2019::sif::export::{self, Format, Palette, Style};
+use spraints_advent_of_code_2019::sif::{self, Image};
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::process;
 
 fn main() {
-    let verbose = false;
-
-    let image = read_image(25, 6);
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let mut verbose = false;
+    let mut dims = vec![];
+    let mut out = None;
+    let mut style = Style::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match *arg {
+            "-v" => verbose = true,
+            "--out" => out = Some(*args.next().unwrap_or_else(|| usage())),
+            "--scale" => style.scale = scale(args.next().unwrap_or_else(|| usage())),
+            "--palette" => {
+                style.palette = Palette::parse(args.next().unwrap_or_else(|| usage()))
+                    .unwrap_or_else(|e| fail(&e))
+            }
+            arg if dims.len() < 2 && !arg.starts_with('-') => dims.push(arg),
+            _ => usage(),
+        }
+    }
+    let format = out.map(|out| {
+        Format::from_path(out).unwrap_or_else(|| fail(&format!("don't know how to write {}", out)))
+    });
+    let size = match dims.as_slice() {
+        [] => None,
+        [width, height] => Some((dimension(width), dimension(height))),
+        _ => usage(),
+    };
+
+    let mut text = String::new();
+    io::stdin()
+        .read_to_string(&mut text)
+        .expect("Error reading from STDIN");
+    let image =
+        sif::decode(&text, size).unwrap_or_else(|e| fail(&format!("Error reading image: {}", e)));
     if verbose {
         dump_image(&image);
     }
 
-    let scores = score_image(&image);
-    let best_layer = scores.iter().fold(
-        [99999; 10],
-        |res, score| if score[0] < res[0] { *score } else { res },
-    );
-    println!("CHECKSUM: {}", best_layer[1] * best_layer[2]);
+    println!("CHECKSUM: {}", image.checksum());
 
     println!("PRETTY PICTURE:");
-    println!("{}", render(&image));
-}
-
-fn dump_image(image: &Image) {
-    for layer in image {
-        println!("------");
-        dump_layer(layer);
-    }
-}
-
-fn dump_layer(layer: &Layer) {
-    for row in layer {
-        println!("{:?}", row);
-    }
-}
-
-type Image = Vec<Layer>;
-type Layer = Vec<Vec<u8>>;
-
-type ImageScore = Vec<LayerScore>;
-type LayerScore = [u32; 10];
-
-const BLACK: u8 = 0;
-const WHITE: u8 = 1;
-const TRANSPARENT: u8 = 2;
+    println!("{}", image.render());
 
-// this color scheme is good for a terminal with black background.
-const RENDERED_BLACK: char = ' ';
-const RENDERED_WHITE: char = '■';
-
-fn render(image: &Image) -> String {
-    let (width, height, _) = get_dims(&image);
-    let mut res = String::new();
-    for i in 0..height {
-        for j in 0..width {
-            let mut rendered_pixel = '?';
-            for layer in image.iter().rev() {
-                let pixel = layer[i][j];
-                match pixel {
-                    BLACK => rendered_pixel = RENDERED_BLACK,
-                    WHITE => rendered_pixel = RENDERED_WHITE,
-                    TRANSPARENT => (),
-                    _ => (),
-                }
-            }
-            res.push(rendered_pixel);
-        }
-        res.push('\n');
+    if let (Some(out), Some(format)) = (out, format) {
+        let data = export::export(&image, format, &style)
+            .unwrap_or_else(|e| fail(&format!("Error writing {}: {}", out, e)));
+        fs::write(out, data).unwrap_or_else(|e| fail(&format!("Error writing {}: {}", out, e)));
     }
-    res
 }
 
-// Returns (width, height, depth)
-// width = number of columns
-// height = number of rows
-// depth = number of layers
-fn get_dims(image: &Image) -> (usize, usize, usize) {
-    let depth = image.len();
-    let height = image[0].len();
-    let width = image[0][0].len();
-    (width, height, depth)
+fn usage() -> ! {
+    eprintln!("Usage: day8 [-v] [--out FILE [--scale N] [--palette SPEC]] [WIDTH HEIGHT] < IMAGE");
+    eprintln!("The size can also be on the first line of the image, like 25x6. If");
+    eprintln!("there isn't one, it's 25x6.");
+    eprintln!("--out writes the picture as PNG, PBM, PGM or PPM, depending on the file's");
+    eprintln!("extension. Each pixel is N pixels square. SPEC changes the color for some");
+    eprintln!("digits, like 0=000000,1=ffffff,2=00000000 (RRGGBB or RRGGBBAA).");
+    process::exit(1);
 }
 
-fn score_image(image: &Image) -> ImageScore {
-    image.iter().map(|layer| score_layer(layer)).collect()
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
 }
 
-fn score_layer(layer: &Layer) -> LayerScore {
-    let mut counts = [0; 10];
-    for row in layer {
-        for cell in row {
-            counts[*cell as usize] += 1;
-        }
-    }
-    counts
+fn dimension(arg: &str) -> usize {
+    sif::parse_dimension(arg).unwrap_or_else(|e| fail(&e))
 }
 
-fn parse_image(input: &str, width: usize, height: usize) -> Image {
-    let mut res = vec![];
-    let mut input = input.chars().map(|c| c.to_digit(10).unwrap() as u8);
-
-    while let Some(val) = input.next() {
-        res.push(build_layer(val, &mut input, width, height));
+fn scale(arg: &str) -> usize {
+    match arg.parse() {
+        Ok(0) | Err(_) => fail(&format!("bad scale '{}': expected a positive number", arg)),
+        Ok(n) => n,
     }
-
-    res
 }
 
-fn build_layer(val: u8, input: &mut dyn Iterator<Item = u8>, width: usize, height: usize) -> Layer {
-    let mut layer = vec![];
-    for i in 0..height {
-        let mut row = vec![];
-        for j in 0..width {
-            if i == 0 && j == 0 {
-                row.push(val);
-            } else {
-                row.push(input.next().unwrap());
-            }
+fn dump_image(image: &Image) {
+    for layer in image.layers() {
+        println!("------");
+        for row in layer.chunks(image.width()) {
+            println!("{:?}", row);
         }
-        layer.push(row);
     }
-    layer
-}
-
-fn read_image(width: usize, height: usize) -> Image {
-    let mut line = String::new();
-    io::stdin()
-        .read_line(&mut line)
-        .expect("Error reading program from STDIN");
-    parse_image(line.trim(), width, height)
 }
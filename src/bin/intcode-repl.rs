@@ -0,0 +1,39 @@
+use spraints_advent_of_code_2019::intcode::ascii::AsciiComputer;
+use spraints_advent_of_code_2019::intcode::{loader, IntCodeComputer};
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let path = match args.as_slice() {
+        [path] => path,
+        _ => {
+            eprintln!("Usage: intcode-repl PROGRAM");
+            process::exit(1);
+        }
+    };
+    let program = loader::load_path(path).unwrap_or_else(|e| {
+        eprintln!("Error loading {}: {}", path, e);
+        process::exit(1);
+    });
+
+    let mut computer = AsciiComputer::new(IntCodeComputer::from_program(&program));
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        for output in computer.run_until_input() {
+            print!("{}", output);
+        }
+        if computer.is_halted() {
+            println!();
+            println!("(halted)");
+            break;
+        }
+        io::stdout().flush().unwrap_or(());
+        match lines.next() {
+            None => break,
+            Some(line) => computer.send_line(&line.expect("Error reading STDIN")),
+        }
+    }
+}
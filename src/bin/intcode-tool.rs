@@ -0,0 +1,221 @@
+use spraints_advent_of_code_2019::grid::Point;
+use spraints_advent_of_code_2019::intcode::loader::{self, Program};
+use spraints_advent_of_code_2019::intcode::screen::{self, Screen};
+use spraints_advent_of_code_2019::intcode::{
+    self, cfg, maze, memview, IntCodeComputer, IntCodeMemory, Item,
+};
+use std::env;
+use std::process;
+use std::sync::mpsc;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+    let (path, args) = match args.as_slice() {
+        ["-f", path, rest @ ..] => (Some(*path), rest),
+        rest => (None, rest),
+    };
+
+    match args {
+        ["run", inputs @ ..] => run(load(path), parse_inputs(inputs)),
+        ["cfg"] => print!("{}", cfg::analyze(&from_start(load(path), "cfg")).to_dot()),
+        ["cfg", "--json"] => print!("{}", cfg::analyze(&from_start(load(path), "cfg")).to_json()),
+        ["selfmod", inputs @ ..] => selfmod(load(path), parse_inputs(inputs)),
+        ["diff", "--files", a, b] => print_diff(
+            &load(Some(a)).patched_memory(),
+            &load(Some(b)).patched_memory(),
+        ),
+        ["diff", a, b, inputs @ ..] => diff_patches(load(path), a, b, parse_inputs(inputs)),
+        ["heatmap", inputs @ ..] => heatmap(load(path), parse_inputs(inputs)),
+        ["pack", out, options @ ..] => pack(load(path), out, options),
+        ["info"] => info(load(path)),
+        ["screen"] => play(load(path), true),
+        ["screen", "--headless"] => play(load(path), false),
+        ["maze"] => explore(load(path)),
+        _ => usage(),
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: intcode-tool [-f PROGRAM] COMMAND");
+    eprintln!("Reads the program from PROGRAM, or STDIN if there's no -f.");
+    eprintln!("Commands:");
+    eprintln!("  run [INPUT...]      run the program and print its output");
+    eprintln!("  cfg [--json]        print the control flow graph as DOT (or JSON)");
+    eprintln!("  selfmod [INPUT...]  run the program and report writes to its own code");
+    eprintln!("  diff PATCH PATCH [INPUT...]");
+    eprintln!("                      run the program with each patch (like 1=12,2=2) and");
+    eprintln!("                      compare the final memory");
+    eprintln!("  diff --files A B    compare two memory images");
+    eprintln!("  heatmap [INPUT...]  run the program and draw memory reads and writes");
+    eprintln!("  pack OUT [--name NAME] [--entry ADDR] [--patch PATCH]");
+    eprintln!("                      save the program in the binary format");
+    eprintln!("                      (only run, screen and maze use the entry point)");
+    eprintln!("  info                describe the program");
+    eprintln!("  screen [--headless] run a program that draws (x, y, tile) triples, moving");
+    eprintln!("                      the joystick toward the ball; print the final frame");
+    eprintln!("  maze                explore with a droid program and print the map, the");
+    eprintln!("                      distance to the target and how long it takes to fill");
+    process::exit(1);
+}
+
+fn load(path: Option<&str>) -> Program {
+    let res = match path {
+        None => loader::load_stdin(),
+        Some(path) => loader::load_path(path),
+    };
+    res.unwrap_or_else(|e| {
+        eprintln!("Error loading {}: {}", path.unwrap_or("STDIN"), e);
+        process::exit(1);
+    })
+}
+
+// The memory for commands that can only start at 0, which is all of them but
+// run and the ones that drive an IntCodeComputer.
+fn from_start(program: Program, command: &str) -> IntCodeMemory {
+    match program.entry {
+        Some(entry) if entry != 0 => {
+            eprintln!(
+                "{} always starts at 0, but {} starts at {}",
+                command, program.name, entry
+            );
+            process::exit(1);
+        }
+        _ => program.patched_memory(),
+    }
+}
+
+fn parse_inputs(args: &[&str]) -> Vec<Item> {
+    args.iter()
+        .map(|s| s.parse().expect("Error parsing input"))
+        .collect()
+}
+
+fn run(program: Program, inputs: Vec<Item>) {
+    let (in_tx, in_rx) = mpsc::channel();
+    let (out_tx, out_rx) = mpsc::channel();
+    for input in inputs {
+        in_tx.send(Some(input)).unwrap();
+    }
+    intcode::run_from(
+        &program.name,
+        program.patched_memory(),
+        program.entry.unwrap_or(0),
+        in_rx,
+        out_tx,
+        false,
+    );
+    for output in out_rx.into_iter().flatten() {
+        println!("{}", output);
+    }
+}
+
+fn selfmod(program: Program, inputs: Vec<Item>) {
+    let (_, trace) = intcode::run_traced("selfmod", from_start(program, "selfmod"), inputs, false);
+    let mods = trace.self_modifications();
+    for m in &mods {
+        println!(
+            "pc {} wrote [{}] in instruction {} ({} times)",
+            m.writer_pc, m.addr, m.target, m.count
+        );
+    }
+    println!(
+        "RAN {} DISTINCT INSTRUCTIONS, {} SELF-MODIFYING WRITES",
+        trace.executed.len(),
+        mods.len()
+    );
+    if mods.is_empty() {
+        println!("SAFE TO CACHE: yes");
+    } else {
+        println!("SAFE TO CACHE: no");
+    }
+}
+
+fn diff_patches(program: Program, a: &str, b: &str, inputs: Vec<Item>) {
+    let program = from_start(program, "diff");
+    let a = memview::parse_patches(a).unwrap_or_else(|e| panic!("{}", e));
+    let b = memview::parse_patches(b).unwrap_or_else(|e| panic!("{}", e));
+    print_diff(
+        &memview::run_patched(&program, &a, inputs.clone()),
+        &memview::run_patched(&program, &b, inputs),
+    );
+}
+
+fn print_diff(a: &[Item], b: &[Item]) {
+    let regions = memview::diff(a, b);
+    for region in &regions {
+        println!("{}", region);
+    }
+    println!("{} CHANGED REGIONS", regions.len());
+}
+
+const HEATMAP_WIDTH: usize = 64;
+
+fn heatmap(program: Program, inputs: Vec<Item>) {
+    let (_, trace) = intcode::run_traced("heatmap", from_start(program, "heatmap"), inputs, false);
+    println!("READS");
+    print!("{}", memview::heatmap(&trace.reads, HEATMAP_WIDTH, 1));
+    println!("WRITES");
+    print!(
+        "{}",
+        memview::heatmap(&trace.write_counts(), HEATMAP_WIDTH, 1)
+    );
+}
+
+fn pack(mut program: Program, out: &str, options: &[&str]) {
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let val = options.next().unwrap_or_else(|| usage_and("missing value"));
+        match *option {
+            "--name" => program.name = val.to_string(),
+            "--entry" => program.entry = Some(val.parse().expect("Error parsing entry")),
+            "--patch" => program
+                .patches
+                .extend(memview::parse_patches(val).unwrap_or_else(|e| panic!("{}", e))),
+            _ => usage_and(&format!("unrecognized option {}", option)),
+        }
+    }
+    program.save(out).expect("Error writing program");
+}
+
+fn usage_and(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    usage()
+}
+
+fn info(program: Program) {
+    println!("NAME:    {}", program.name);
+    println!("ENTRY:   {}", program.entry.unwrap_or(0));
+    println!("PATCHES: {:?}", program.patches);
+    println!("CELLS:   {}", program.memory.len());
+}
+
+fn play(program: Program, live: bool) {
+    let mut computer = IntCodeComputer::from_program(&program);
+    let mut screen = Screen::new();
+    screen::play(&mut computer, &mut screen, screen::follow_ball, live);
+    if !live {
+        print!("{}", screen.render());
+    }
+}
+
+fn explore(program: Program) {
+    let mut droid = IntCodeComputer::from_program(&program);
+    let map = maze::explore(&mut droid).unwrap_or_else(|e| {
+        eprintln!("Error exploring: {}", e);
+        process::exit(1);
+    });
+    print!("{}", map.render());
+    match map.target() {
+        None => println!("NO TARGET FOUND"),
+        Some(target) => {
+            println!("TARGET:    {}", target);
+            println!(
+                "DISTANCE:  {}",
+                map.distance(Point::ORIGIN, target).unwrap()
+            );
+            println!("FILL TIME: {}", map.fill_time(target));
+        }
+    }
+}
@@ -0,0 +1,357 @@
+////////
+// FUEL
+//
+// Fuel for modules, given their masses. The simple model only counts fuel for
+// the module. The recursive model also counts fuel for the fuel, and so on,
+// until the extra fuel doesn't need any more.
+//
+// A manifest has one module per line: a mass, optionally with a name in front
+// of it, like "left booster 12345".
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(2, simple(12));
+        assert_eq!(2, simple(14));
+        assert_eq!(654, simple(1969));
+        assert_eq!(33583, simple(100756));
+        assert_eq!(-1, simple(5));
+        assert_eq!(-2, simple(0));
+
+        assert_eq!(2, recursive(14));
+        assert_eq!(966, recursive(1969));
+        assert_eq!(50346, recursive(100756));
+        assert_eq!(0, recursive(0));
+        assert_eq!(0, recursive(5));
+        assert_eq!(Some(-3), total(vec![0, 5, 6], Model::Simple));
+
+        assert_eq!(
+            Some(34241),
+            total(vec![12, 14, 1969, 100756], Model::Simple)
+        );
+        assert_eq!(
+            Some(51316),
+            total(vec![12, 14, 1969, 100756], Model::Recursive)
+        );
+        assert_eq!(Some(0), total(vec![], Model::Recursive));
+    }
+
+    #[test]
+    fn test_calculate() {
+        let report = calculate("12\n\n 1969 \nabc\n-4\n100756\n".lines());
+        assert_eq!(
+            vec![
+                ModuleFuel {
+                    line: 1,
+                    name: None,
+                    mass: 12,
+                    simple: 2,
+                    recursive: 2
+                },
+                ModuleFuel {
+                    line: 3,
+                    name: None,
+                    mass: 1969,
+                    simple: 654,
+                    recursive: 966
+                },
+                ModuleFuel {
+                    line: 6,
+                    name: None,
+                    mass: 100756,
+                    simple: 33583,
+                    recursive: 50346
+                },
+            ],
+            report.modules
+        );
+        assert_eq!(34239, report.simple);
+        assert_eq!(51314, report.recursive);
+        let errors: Vec<String> = report.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            vec![
+                "line 4 ('abc'): bad mass: invalid digit found in string",
+                "line 5 ('-4'): mass can't be negative",
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let big = Mass::MAX.to_string();
+        let report = calculate(vec![big.as_str(); 4]);
+        assert_eq!(2, report.modules.len());
+        assert_eq!(2, report.errors.len());
+        assert_eq!(3, report.errors[0].line);
+        assert_eq!("total fuel is too big", report.errors[0].message);
+        assert_eq!(None, total(vec![Mass::MAX; 4], Model::Simple));
+    }
+
+    #[test]
+    fn test_names_and_memo() {
+        let mut calc = Calculator::new();
+        let report = calc.calculate(vec!["left booster  1969", "1969", "tank, \"big\" 100756"]);
+        let names: Vec<Option<&str>> = report.modules.iter().map(|m| m.name.as_deref()).collect();
+        assert_eq!(
+            vec![Some("left booster"), None, Some("tank, \"big\"")],
+            names
+        );
+        assert_eq!(966 * 2 + 50346, report.recursive);
+        assert_eq!(1, calc.hits());
+        assert_eq!(calc.recursive(100756), recursive(100756));
+        assert_eq!(2, calc.hits());
+        assert_eq!(
+            "line,name,mass,fuel,fuel_with_fuel\n\
+             1,left booster,1969,654,966\n\
+             2,,1969,654,966\n\
+             3,\"tank, \"\"big\"\"\",100756,33583,50346\n\
+             ,TOTAL,,34891,52278\n",
+            report.to_csv()
+        );
+    }
+
+    #[test]
+    fn test_day1_input() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let report = load_manifest(format!("{}/data/day1/input", dir)).unwrap();
+        assert!(report.errors.is_empty());
+        assert_eq!(100, report.modules.len());
+        assert_eq!(3348909, report.simple);
+        assert_eq!(5020494, report.recursive);
+
+        // Name every module, write the manifest out, and read it back.
+        let manifest: String = report
+            .modules
+            .iter()
+            .map(|m| format!("module {} {}\n", m.line, m.mass))
+            .collect();
+        let path = std::env::temp_dir().join(format!("fuel-test-{}.txt", std::process::id()));
+        fs::write(&path, manifest).unwrap();
+        let named = load_manifest(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(report.simple, named.simple);
+        assert_eq!(report.recursive, named.recursive);
+        assert_eq!(Some("module 7"), named.modules[6].name.as_deref());
+
+        let csv = named.to_csv();
+        assert_eq!(report.modules.len() + 2, csv.lines().count());
+        assert_eq!(Some(",TOTAL,,3348909,5020494"), csv.lines().last());
+    }
+}
+
+pub type Mass = i64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Model {
+    Simple,
+    Recursive,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModuleFuel {
+    pub line: usize,
+    pub name: Option<String>,
+    pub mass: Mass,
+    pub simple: Mass,
+    pub recursive: Mass,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FuelError {
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+// Everything from reading a list of masses. Lines with errors are left out of
+// the totals.
+#[derive(Debug, Default, PartialEq)]
+pub struct Report {
+    pub modules: Vec<ModuleFuel>,
+    pub errors: Vec<FuelError>,
+    pub simple: Mass,
+    pub recursive: Mass,
+}
+
+// Remembers the recursive fuel for every mass it has seen, including the
+// masses of the fuel along the way, so repeated masses are free.
+#[derive(Debug, Default)]
+pub struct Calculator {
+    memo: HashMap<Mass, Mass>,
+    hits: usize,
+}
+
+impl fmt::Display for FuelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} ('{}'): {}", self.line, self.text, self.message)
+    }
+}
+
+// Just mass / 3 - 2, so anything under 6 comes out negative.
+pub fn simple(mass: Mass) -> Mass {
+    mass / 3 - 2
+}
+
+// Fuel that would be 0 or negative needs none.
+pub fn recursive(mass: Mass) -> Mass {
+    let mut res = 0;
+    let mut fuel = simple(mass);
+    while fuel > 0 {
+        res += fuel;
+        fuel = simple(fuel);
+    }
+    res
+}
+
+pub fn fuel(mass: Mass, model: Model) -> Mass {
+    match model {
+        Model::Simple => simple(mass),
+        Model::Recursive => recursive(mass),
+    }
+}
+
+// The total fuel for some masses, or None if it doesn't fit in a Mass.
+pub fn total<I: IntoIterator<Item = Mass>>(masses: I, model: Model) -> Option<Mass> {
+    masses
+        .into_iter()
+        .try_fold(0 as Mass, |sum, mass| sum.checked_add(fuel(mass, model)))
+}
+
+pub fn parse_mass(text: &str) -> Result<Mass, String> {
+    let mass: Mass = text.parse().map_err(|e| format!("bad mass: {}", e))?;
+    if mass < 0 {
+        return Err("mass can't be negative".to_string());
+    }
+    Ok(mass)
+}
+
+// Splits "name mass" into its parts. The name is optional and can have
+// spaces in it.
+fn parse_line(text: &str) -> (Option<String>, &str) {
+    match text.rfind(char::is_whitespace) {
+        None => (None, text),
+        Some(i) => (Some(text[..i].trim_end().to_string()), &text[i + 1..]),
+    }
+}
+
+// Reads a manifest. Blank lines are skipped, and line numbers start at 1.
+pub fn calculate<I, S>(lines: I) -> Report
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    Calculator::new().calculate(lines)
+}
+
+pub fn load_manifest<P: AsRef<Path>>(path: P) -> io::Result<Report> {
+    Ok(calculate(fs::read_to_string(path)?.lines()))
+}
+
+impl Calculator {
+    pub fn new() -> Calculator {
+        Calculator::default()
+    }
+
+    // How many times a mass was already known.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn recursive(&mut self, mass: Mass) -> Mass {
+        if let Some(res) = self.memo.get(&mass) {
+            self.hits += 1;
+            return *res;
+        }
+        let fuel = simple(mass);
+        let res = if fuel > 0 {
+            fuel + self.recursive(fuel)
+        } else {
+            0
+        };
+        self.memo.insert(mass, res);
+        res
+    }
+
+    pub fn calculate<I, S>(&mut self, lines: I) -> Report
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut res = Report::default();
+        for (i, line) in lines.into_iter().enumerate() {
+            let text = line.as_ref().trim();
+            if text.is_empty() {
+                continue;
+            }
+            let err = |message: String| FuelError {
+                line: i + 1,
+                text: text.to_string(),
+                message,
+            };
+            let (name, mass) = parse_line(text);
+            let mass = match parse_mass(mass) {
+                Ok(mass) => mass,
+                Err(message) => {
+                    res.errors.push(err(message));
+                    continue;
+                }
+            };
+            let module = ModuleFuel {
+                line: i + 1,
+                name,
+                mass,
+                simple: simple(mass),
+                recursive: self.recursive(mass),
+            };
+            match (
+                res.simple.checked_add(module.simple),
+                res.recursive.checked_add(module.recursive),
+            ) {
+                (Some(simple), Some(recursive)) => {
+                    res.simple = simple;
+                    res.recursive = recursive;
+                    res.modules.push(module);
+                }
+                _ => res.errors.push(err("total fuel is too big".to_string())),
+            }
+        }
+        res
+    }
+}
+
+impl Report {
+    // One row per module, then a row with the totals.
+    pub fn to_csv(&self) -> String {
+        let mut res = "line,name,mass,fuel,fuel_with_fuel\n".to_string();
+        for module in &self.modules {
+            res.push_str(&format!(
+                "{},{},{},{},{}\n",
+                module.line,
+                csv_field(module.name.as_deref().unwrap_or("")),
+                module.mass,
+                module.simple,
+                module.recursive
+            ));
+        }
+        res.push_str(&format!(",TOTAL,,{},{}\n", self.simple, self.recursive));
+        res
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
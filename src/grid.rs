@@ -0,0 +1,447 @@
+////////
+// GRID
+//
+// Points and directions on a 2D grid, plus a couple of ways to store things
+// on one. y grows downward, so "up" is toward the first row when rendered.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Add;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_points_and_directions() {
+        let p = Point::new(3, -4);
+        assert_eq!(7, p.manhattan(Point::ORIGIN));
+        assert_eq!(Point::new(3, -5), p.step(Direction::Up));
+        assert_eq!(Point::new(0, -4), p.step_by(Direction::Left, 3));
+        assert_eq!(
+            vec![
+                Point::new(3, -5),
+                Point::new(4, -4),
+                Point::new(3, -3),
+                Point::new(2, -4)
+            ],
+            p.neighbors().collect::<Vec<_>>()
+        );
+        assert_eq!("(3, -4)", p.to_string());
+
+        assert_eq!(Direction::Left, Direction::Up.turn_left());
+        assert_eq!(Direction::Right, Direction::Up.turn_right());
+        assert_eq!(Direction::Down, Direction::Up.reverse());
+        for dir in &Direction::ALL {
+            assert_eq!(*dir, dir.turn_left().turn_right());
+            assert_eq!(
+                *dir,
+                dir.turn_right().turn_right().turn_right().turn_right()
+            );
+        }
+        assert_eq!(Some(Direction::Down), Direction::from_char('D'));
+        assert_eq!(None, Direction::from_char('E'));
+        assert_eq!(Some(Direction::Right), Direction::from_compass('E'));
+        assert_eq!(None, Direction::from_compass('R'));
+        assert_eq!(None, Direction::from_char('x'));
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(None, Bounds::from_points(vec![]));
+        let bounds =
+            Bounds::from_points(vec![Point::new(2, 1), Point::new(-1, 3), Point::new(0, 0)])
+                .unwrap();
+        assert_eq!(Point::new(-1, 0), bounds.min);
+        assert_eq!(Point::new(2, 3), bounds.max);
+        assert_eq!((4, 4), (bounds.width(), bounds.height()));
+        assert!(bounds.contains(Point::new(-1, 3)));
+        assert!(!bounds.contains(Point::new(3, 3)));
+        assert_eq!(16, bounds.points().count());
+        assert_eq!(Some(Point::new(0, 0)), bounds.points().nth(1));
+    }
+
+    #[test]
+    fn test_dense_grid() {
+        let mut grid = DenseGrid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(Some(&6), grid.get(Point::new(2, 1)));
+        assert_eq!(None, grid.get(Point::new(3, 0)));
+        assert_eq!(None, grid.get(Point::new(0, -1)));
+        grid.set(Point::new(0, 1), 0);
+        *grid.get_mut(Point::new(1, 0)).unwrap() += 10;
+        assert_eq!(
+            vec![&[1, 12, 3][..], &[0, 5, 6][..]],
+            grid.rows().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Point::new(1, 0), Point::new(0, 1)],
+            grid.neighbors(Point::ORIGIN).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "#.#\n.#.\n",
+            grid.render(|_, val| if val % 2 == 1 { '#' } else { '.' })
+        );
+        assert_eq!(DenseGrid::new(3, 2, 7).values().sum::<i32>(), 42);
+    }
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid = SparseGrid::new();
+        assert!(grid.is_empty());
+        assert_eq!("", grid.render(|_, _: Option<&char>| '?'));
+        assert_eq!(None, grid.set(Point::new(1, 1), 'a'));
+        assert_eq!(Some('a'), grid.set(Point::new(1, 1), 'b'));
+        grid.set(Point::new(-1, 0), 'c');
+        *grid.get_or_insert_with(Point::new(0, 2), || 'x') = 'd';
+        assert_eq!(3, grid.len());
+        assert!(grid.contains(Point::new(0, 2)));
+        assert_eq!(Some(&'b'), grid.get(Point::new(1, 1)));
+        assert_eq!(
+            "c..\n..b\n.d.\n",
+            grid.render(|_, val| *val.unwrap_or(&'.'))
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+// The smallest rectangle that holds a set of points. Both corners are
+// included.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+// A fixed-size grid with a value in every cell. (0, 0) is the top left.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DenseGrid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+// An unbounded grid that only stores the cells that have been set.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    pub fn manhattan(self, other: Point) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn step(self, dir: Direction) -> Point {
+        self + dir.delta()
+    }
+
+    pub fn step_by(self, dir: Direction, count: i64) -> Point {
+        let delta = dir.delta();
+        Point::new(self.x + delta.x * count, self.y + delta.y * count)
+    }
+
+    // The four points next to this one, in the same order as Direction::ALL.
+    pub fn neighbors(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.iter().map(move |dir| self.step(*dir))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    // U, R, D or L.
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            'U' => Some(Direction::Up),
+            'R' => Some(Direction::Right),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    // N, E, S or W.
+    pub fn from_compass(c: char) -> Option<Direction> {
+        match c {
+            'N' => Some(Direction::Up),
+            'E' => Some(Direction::Right),
+            'S' => Some(Direction::Down),
+            'W' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn turn_left(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn reverse(self) -> Direction {
+        self.turn_left().turn_left()
+    }
+
+    pub fn delta(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+}
+
+impl Bounds {
+    pub fn from_points<I: IntoIterator<Item = Point>>(points: I) -> Option<Bounds> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut res = Bounds {
+            min: first,
+            max: first,
+        };
+        for p in points {
+            res.include(p);
+        }
+        Some(res)
+    }
+
+    pub fn include(&mut self, p: Point) {
+        self.min = Point::new(self.min.x.min(p.x), self.min.y.min(p.y));
+        self.max = Point::new(self.max.x.max(p.x), self.max.y.max(p.y));
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    // Row by row, left to right.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+
+    // One character per point, with a newline after each row.
+    pub fn render<F: FnMut(Point) -> char>(&self, mut glyph: F) -> String {
+        let mut res = String::new();
+        for y in self.min.y..=self.max.y {
+            for x in self.min.x..=self.max.x {
+                res.push(glyph(Point::new(x, y)));
+            }
+            res.push('\n');
+        }
+        res
+    }
+}
+
+impl<T: Clone> DenseGrid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> DenseGrid<T> {
+        DenseGrid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> DenseGrid<T> {
+    // Every row has to be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> DenseGrid<T> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        let mut cells = Vec::with_capacity(width * height);
+        for row in rows {
+            assert_eq!(width, row.len(), "rows must all be the same length");
+            cells.extend(row);
+        }
+        DenseGrid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        Some(Bounds {
+            min: Point::ORIGIN,
+            max: Point::new(self.width as i64 - 1, self.height as i64 - 1),
+        })
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.index(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.index(p).map(move |i| &mut self.cells[i])
+    }
+
+    // Panics if the point is outside the grid.
+    pub fn set(&mut self, p: Point, val: T) {
+        match self.index(p) {
+            Some(i) => self.cells[i] = val,
+            None => panic!("{} is outside of a {}x{} grid", p, self.width, self.height),
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    // The neighbors of a point that are on the grid.
+    pub fn neighbors(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbors().filter(move |n| self.index(*n).is_some())
+    }
+
+    pub fn render<F: FnMut(Point, &T) -> char>(&self, mut glyph: F) -> String {
+        match self.bounds() {
+            None => String::new(),
+            Some(bounds) => bounds.render(|p| glyph(p, self.get(p).unwrap())),
+        }
+    }
+
+    fn index(&self, p: Point) -> Option<usize> {
+        if p.x < 0 || p.y < 0 || p.x as usize >= self.width || p.y as usize >= self.height {
+            None
+        } else {
+            Some(p.y as usize * self.width + p.x as usize)
+        }
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    // Returns the old value, if there was one.
+    pub fn set(&mut self, p: Point, val: T) -> Option<T> {
+        self.cells.insert(p, val)
+    }
+
+    pub fn get_or_insert_with<F: FnOnce() -> T>(&mut self, p: Point, f: F) -> &mut T {
+        self.cells.entry(p).or_insert_with(f)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(p, val)| (*p, val))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.values()
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(self.cells.keys().cloned())
+    }
+
+    // Draws the bounding box of the cells that are set. Cells in the box that
+    // aren't set get None.
+    pub fn render<F: FnMut(Point, Option<&T>) -> char>(&self, mut glyph: F) -> String {
+        match self.bounds() {
+            None => String::new(),
+            Some(bounds) => bounds.render(|p| glyph(p, self.get(p))),
+        }
+    }
+}
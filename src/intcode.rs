@@ -1,9 +1,19 @@
 //////////
 // INTCODE
 
-use std::collections::HashMap;
-use std::io;
+use std::collections::{HashMap, VecDeque};
 use std::sync::mpsc::{self, Receiver, Sender};
+use trace::Trace;
+
+pub mod ascii;
+pub mod cfg;
+pub mod loader;
+pub mod maze;
+pub mod memview;
+pub mod robot;
+pub mod screen;
+pub mod symbolic;
+pub mod trace;
 
 #[cfg(test)]
 mod tests {
@@ -11,6 +21,7 @@ mod tests {
     use std::sync::mpsc;
 
     #[test]
+    #[allow(clippy::collapsible_match)]
     fn test_day9_ex1_quine() {
         let program = vec![
             109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
@@ -37,6 +48,23 @@ mod tests {
         assert!(n < 10_000_000_000_000_000);
     }
 
+    #[test]
+    fn test_pause_for_input() {
+        // out = in * 2, twice
+        let program = vec![3, 9, 1002, 9, 2, 9, 4, 9, 1105, 1, 0];
+        let mut computer = IntCodeComputer::new("test", program);
+        assert_eq!(Status::NeedInput, computer.resume());
+        computer.push_input(21);
+        assert_eq!(Status::Output(42), computer.resume());
+
+        let mut saved = computer.clone();
+        computer.push_input(5);
+        assert_eq!(Status::Output(10), computer.resume());
+        saved.push_input(6);
+        assert_eq!(Status::Output(12), saved.resume());
+        assert_eq!(Status::NeedInput, saved.resume());
+    }
+
     fn dead_receiver() -> Receiver<Option<Item>> {
         mpsc::channel().1
     }
@@ -44,28 +72,36 @@ mod tests {
 
 pub type Item = i64;
 
-struct IntCodeComputer {
+// A computer that runs until it has something to say or needs something, then
+// pauses. Clone it to save its state.
+#[derive(Clone)]
+pub struct IntCodeComputer {
     name: String,
     memory: IntCodeMemory,
     extra_memory: HashMap<usize, Item>,
-    inputs: Receiver<Option<Item>>,
-    outputs: Sender<Option<Item>>,
+    inputs: VecDeque<Item>,
+    output: Option<Item>,
     verbose: bool,
     pc: usize,
     relative_base: Item,
+    trace: Option<Trace>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Output(Item),
+    // The program wants to read, and there's nothing queued up.
+    NeedInput,
+    Halted,
 }
 
 pub type IntCodeMemory = Vec<Item>;
 
 pub fn read_program() -> IntCodeMemory {
-    let mut line = String::new();
-    io::stdin()
-        .read_line(&mut line)
-        .expect("Error reading program from STDIN");
-    let parts = line.trim().split(',');
-    parts
-        .map(|s| s.parse().expect("Error parsing int"))
-        .collect()
+    match loader::load_stdin() {
+        Ok(program) => program.patched_memory(),
+        Err(e) => panic!("Error reading program from STDIN: {}", e),
+    }
 }
 
 pub fn run_easy(name: &str, memory: IntCodeMemory, inputs: Vec<Item>, verbose: bool) -> Vec<Item> {
@@ -77,12 +113,38 @@ pub fn run_easy(name: &str, memory: IntCodeMemory, inputs: Vec<Item>, verbose: b
     recv_all(out_rx)
 }
 
+// Like run_easy, but also records which instructions ran and what they wrote.
+pub fn run_traced(
+    name: &str,
+    memory: IntCodeMemory,
+    inputs: Vec<Item>,
+    verbose: bool,
+) -> (Vec<Item>, Trace) {
+    let mut computer = IntCodeComputer::new(name, memory);
+    computer.verbose = verbose;
+    computer.trace = Some(Trace::default());
+    for input in inputs {
+        computer.push_input(input);
+    }
+
+    let mut outputs = vec![];
+    loop {
+        match computer.resume() {
+            Status::Output(val) => outputs.push(val),
+            Status::NeedInput => panic!("{}: ran out of inputs", computer.name),
+            Status::Halted => break,
+        }
+    }
+    (outputs, computer.trace.unwrap_or_default())
+}
+
 fn send_all(inputs: Vec<Item>, in_tx: Sender<Option<Item>>) {
     for input in inputs {
         in_tx.send(Some(input)).expect("send should be ok");
     }
 }
 
+#[allow(clippy::manual_flatten)]
 fn recv_all(out_rx: Receiver<Option<Item>>) -> Vec<Item> {
     let mut res = vec![];
     for val in out_rx {
@@ -100,57 +162,133 @@ pub fn run(
     outputs: Sender<Option<Item>>,
     verbose: bool,
 ) -> IntCodeMemory {
-    let mut computer = IntCodeComputer {
-        name: name.to_string(),
-        memory,
-        extra_memory: HashMap::new(),
-        inputs,
-        outputs,
-        verbose,
-        pc: 0,
-        relative_base: 0,
-    };
+    run_from(name, memory, 0, inputs, outputs, verbose)
+}
+
+// Like run, but starts executing at `entry` instead of 0.
+pub fn run_from(
+    name: &str,
+    memory: IntCodeMemory,
+    entry: usize,
+    inputs: Receiver<Option<Item>>,
+    outputs: Sender<Option<Item>>,
+    verbose: bool,
+) -> IntCodeMemory {
+    let mut computer = IntCodeComputer::new(name, memory);
+    computer.verbose = verbose;
+    computer.pc = entry;
+
+    loop {
+        match computer.resume() {
+            Status::Output(val) => {
+                if let Err(msg) = outputs.send(Some(val)) {
+                    panic!("{}: send error: {}", name, msg);
+                }
+            }
+            Status::NeedInput => match inputs.recv() {
+                Err(msg) => panic!("{}: receive error: {}", name, msg),
+                Ok(None) => panic!(
+                    "{}: expected a value to be available, but found None!",
+                    name
+                ),
+                Ok(Some(val)) => computer.push_input(val),
+            },
+            Status::Halted => break,
+        }
+    }
 
-    let opcodes = [
-        op_zero,
-        op_add,                  // 1
-        op_mult,                 // 2
-        op_input,                // 3
-        op_output,               // 4
-        op_jump_if_true,         // 5
-        op_jump_if_false,        // 6
-        op_lt,                   // 7
-        op_eq,                   // 8
-        op_relative_base_offset, // 9
-    ];
+    outputs.send(None).unwrap_or(());
 
-    if computer.verbose {
-        println!("[{}/{}]", computer.name, computer.pc);
+    computer.memory
+}
+
+impl IntCodeComputer {
+    pub fn new(name: &str, memory: IntCodeMemory) -> IntCodeComputer {
+        IntCodeComputer {
+            name: name.to_string(),
+            memory,
+            extra_memory: HashMap::new(),
+            inputs: VecDeque::new(),
+            output: None,
+            verbose: false,
+            pc: 0,
+            relative_base: 0,
+            trace: None,
+        }
     }
-    loop {
-        if computer.verbose {
+
+    pub fn from_program(program: &loader::Program) -> IntCodeComputer {
+        let mut computer = IntCodeComputer::new(&program.name, program.patched_memory());
+        computer.pc = program.entry.unwrap_or(0);
+        computer
+    }
+
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.verbose = verbose;
+    }
+
+    pub fn push_input(&mut self, val: Item) {
+        self.inputs.push_back(val);
+    }
+
+    pub fn memory(&self) -> &IntCodeMemory {
+        &self.memory
+    }
+
+    // Runs until the program outputs something, needs input that hasn't been
+    // pushed yet, or halts. Calling it again picks up where it left off.
+    pub fn resume(&mut self) -> Status {
+        if self.verbose {
+            println!("[{}/{}]", self.name, self.pc);
+        }
+        loop {
+            if let Some(status) = self.step() {
+                return status;
+            }
+        }
+    }
+
+    // Runs one instruction, and returns a status if it's one that resume stops
+    // for.
+    fn step(&mut self) -> Option<Status> {
+        let opcodes = [
+            op_zero,
+            op_add,                  // 1
+            op_mult,                 // 2
+            op_input,                // 3
+            op_output,               // 4
+            op_jump_if_true,         // 5
+            op_jump_if_false,        // 6
+            op_lt,                   // 7
+            op_eq,                   // 8
+            op_relative_base_offset, // 9
+        ];
+
+        if self.verbose {
             println!(
                 " {}: ... {:?} ...",
-                computer.name,
-                computer.memory.get(computer.pc..computer.pc + 4)
+                self.name,
+                self.memory.get(self.pc..self.pc + 4)
             );
         }
-        let op = computer.memory[computer.pc] as usize;
+        let op = self.memory[self.pc] as usize;
         let opcode = op % 100;
+        if opcode == 3 && self.inputs.is_empty() {
+            return Some(Status::NeedInput);
+        }
+        if let Some(trace) = &mut self.trace {
+            trace.exec(self.pc, param_count(opcode).unwrap_or(0) + 1);
+        }
         if opcode == 99 {
-            break;
-        } else {
-            let opfn = opcodes[opcode];
-            opfn(&mut computer, modes(op / 100));
-            if computer.verbose {
-                println!("[{}/{}]", computer.name, computer.pc);
-            }
+            return Some(Status::Halted);
         }
+        let opfn = opcodes[opcode];
+        opfn(self, modes(op / 100));
+        if self.verbose {
+            println!("[{}/{}]", self.name, self.pc);
+        }
+        self.output.take().map(Status::Output)
     }
-
-    computer.outputs.send(None).unwrap_or(());
-
-    computer.memory
 }
 
 fn modes(modes: usize) -> IntCodeModesIter {
@@ -161,23 +299,51 @@ struct IntCodeModesIter {
     modes: usize,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum ModeType {
     Position,
     Immediate,
     Relative,
 }
 
+impl IntCodeModesIter {
+    // Like calling next() `count` times, but gives None for an unrecognized mode
+    // instead of panicking. Useful when looking at memory that might not be code.
+    fn take_checked(&mut self, count: usize) -> Option<Vec<ModeType>> {
+        (0..count).map(|_| self.next_checked()).collect()
+    }
+
+    fn next_checked(&mut self) -> Option<ModeType> {
+        let cur = self.modes % 10;
+        self.modes /= 10;
+        match cur {
+            0 => Some(ModeType::Position),
+            1 => Some(ModeType::Immediate),
+            2 => Some(ModeType::Relative),
+            _ => None,
+        }
+    }
+}
+
 impl Iterator for IntCodeModesIter {
     type Item = ModeType;
     fn next(&mut self) -> Option<Self::Item> {
         let cur = self.modes % 10;
-        self.modes = self.modes / 10;
-        Some(match cur {
-            0 => ModeType::Position,
-            1 => ModeType::Immediate,
-            2 => ModeType::Relative,
-            _ => panic!("Unrecognized mode {}", cur),
-        })
+        match self.next_checked() {
+            None => panic!("Unrecognized mode {}", cur),
+            mode => mode,
+        }
+    }
+}
+
+// Returns the number of parameters that follow the opcode.
+fn param_count(opcode: usize) -> Option<usize> {
+    match opcode {
+        1 | 2 | 7 | 8 => Some(3),
+        3 | 4 | 9 => Some(1),
+        5 | 6 => Some(2),
+        99 => Some(0),
+        _ => None,
     }
 }
 
@@ -188,7 +354,7 @@ struct Params<'a> {
 }
 
 impl Params<'_> {
-    fn new(computer: &mut IntCodeComputer, modes: IntCodeModesIter) -> Params {
+    fn new(computer: &mut IntCodeComputer, modes: IntCodeModesIter) -> Params<'_> {
         let off = computer.pc + 1;
         Params {
             computer,
@@ -239,6 +405,9 @@ fn get_mem(computer: &mut IntCodeComputer, addr: usize) -> Item {
     if computer.verbose {
         println!(" {}: GET [{}] => {:?}", computer.name, addr, val);
     }
+    if let Some(trace) = &mut computer.trace {
+        trace.read(addr);
+    }
     *(val.unwrap_or(&0))
 }
 
@@ -246,6 +415,9 @@ fn set_mem(computer: &mut IntCodeComputer, addr: usize, val: Item) {
     if computer.verbose {
         println!(" {}: SET [{}] = {}", computer.name, addr, val);
     }
+    if let Some(trace) = &mut computer.trace {
+        trace.write(computer.pc, addr);
+    }
     if addr < MAX_MEMORY_VEC {
         ensure_mem(computer, addr);
         computer.memory[addr] = val;
@@ -289,22 +461,17 @@ fn op_mult(computer: &mut IntCodeComputer, modes: IntCodeModesIter) {
 }
 
 fn op_input(computer: &mut IntCodeComputer, modes: IntCodeModesIter) {
-    match computer.inputs.recv() {
-        Err(msg) => panic!("{}: receive error: {}", computer.name, msg),
-        Ok(optval) => {
-            match optval {
-                None => panic!(
-                    "{}: expected a value to be available, but found None!",
-                    computer.name
-                ),
-                Some(val) => {
-                    if computer.verbose {
-                        println!("  ({}: read: {})", computer.name, val);
-                    }
-                    let mut params = Params::new(computer, modes);
-                    params.write_next(val);
-                }
-            };
+    match computer.inputs.pop_front() {
+        None => panic!(
+            "{}: expected a value to be available, but found None!",
+            computer.name
+        ),
+        Some(val) => {
+            if computer.verbose {
+                println!("  ({}: read: {})", computer.name, val);
+            }
+            let mut params = Params::new(computer, modes);
+            params.write_next(val);
         }
     };
     computer.pc += 2;
@@ -316,9 +483,7 @@ fn op_output(computer: &mut IntCodeComputer, modes: IntCodeModesIter) {
     if computer.verbose {
         println!("  ({}: output: {})", computer.name, val);
     }
-    if let Err(msg) = computer.outputs.send(Some(val)) {
-        panic!("{}: send error: {}", computer.name, msg);
-    }
+    computer.output = Some(val);
     computer.pc += 2;
 }
 
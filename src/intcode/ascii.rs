@@ -0,0 +1,144 @@
+/////////
+// ASCII
+//
+// Lots of programs talk in text: they print characters and read typed lines.
+// This wraps a computer so that output comes back as text (with anything
+// outside of ASCII passed through as a number) and input goes in a line at a
+// time.
+
+use super::{IntCodeComputer, Item, Status};
+use std::fmt;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            vec![
+                AsciiOutput::Text("Hi\n".to_string()),
+                AsciiOutput::Value(1000),
+                AsciiOutput::Value(-1),
+                AsciiOutput::Text("!".to_string()),
+            ],
+            decode(&[72, 105, 10, 1000, -1, 33])
+        );
+        assert_eq!("[1000]", format!("{}", AsciiOutput::Value(1000)));
+    }
+
+    #[test]
+    fn test_encode_line() {
+        assert_eq!(vec![78, 79, 82, 84, 72, 10], encode_line("NORTH"));
+        assert_eq!(vec![10], encode_line(""));
+    }
+
+    #[test]
+    fn test_echo() {
+        // Prints "Hi\n", echoes one line, then prints 1000.
+        let program = vec![
+            104, 72, 104, 105, 104, 10, 3, 100, 4, 100, 1008, 100, 10, 101, 1006, 101, 6, 104,
+            1000, 99,
+        ];
+        let mut computer = AsciiComputer::new(IntCodeComputer::new("echo", program));
+        assert_eq!(
+            vec![AsciiOutput::Text("Hi\n".to_string())],
+            computer.run_until_input()
+        );
+        assert!(!computer.is_halted());
+
+        computer.send_line("ok");
+        assert_eq!(
+            vec![
+                AsciiOutput::Text("ok\n".to_string()),
+                AsciiOutput::Value(1000)
+            ],
+            computer.run_until_input()
+        );
+        assert!(computer.is_halted());
+        assert_eq!(Vec::<AsciiOutput>::new(), computer.run_until_input());
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AsciiOutput {
+    Text(String),
+    Value(Item),
+}
+
+impl fmt::Display for AsciiOutput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AsciiOutput::Text(text) => write!(f, "{}", text),
+            AsciiOutput::Value(val) => write!(f, "[{}]", val),
+        }
+    }
+}
+
+pub struct AsciiComputer {
+    computer: IntCodeComputer,
+    halted: bool,
+}
+
+// Groups runs of characters into text.
+pub fn decode(values: &[Item]) -> Vec<AsciiOutput> {
+    let mut res = vec![];
+    for val in values {
+        push_output(&mut res, *val);
+    }
+    res
+}
+
+// The values to feed in for one line of input, including the newline.
+pub fn encode_line(line: &str) -> Vec<Item> {
+    line.chars()
+        .chain("\n".chars())
+        .map(|c| c as Item)
+        .collect()
+}
+
+fn push_output(res: &mut Vec<AsciiOutput>, val: Item) {
+    if (0..128).contains(&val) {
+        let c = val as u8 as char;
+        if let Some(AsciiOutput::Text(text)) = res.last_mut() {
+            text.push(c);
+        } else {
+            res.push(AsciiOutput::Text(c.to_string()));
+        }
+    } else {
+        res.push(AsciiOutput::Value(val));
+    }
+}
+
+impl AsciiComputer {
+    pub fn new(computer: IntCodeComputer) -> AsciiComputer {
+        AsciiComputer {
+            computer,
+            halted: false,
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    pub fn send_line(&mut self, line: &str) {
+        for val in encode_line(line) {
+            self.computer.push_input(val);
+        }
+    }
+
+    // Runs until the program wants another line or halts, and returns
+    // everything it printed along the way.
+    pub fn run_until_input(&mut self) -> Vec<AsciiOutput> {
+        let mut res = vec![];
+        while !self.halted {
+            match self.computer.resume() {
+                Status::Output(val) => push_output(&mut res, val),
+                Status::NeedInput => break,
+                Status::Halted => self.halted = true,
+            }
+        }
+        res
+    }
+}
@@ -0,0 +1,383 @@
+//////////////////////
+// CONTROL FLOW GRAPH
+//
+// Splits a program into basic blocks by following the code from address 0.
+// Jump targets given in immediate mode are resolved statically; anything else
+// is marked as indirect. Cells that the program writes to with a fixed address
+// are flagged, since jumps and instructions there can change at runtime.
+
+use super::{modes, param_count, IntCodeMemory, Item, ModeType};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day9_quine() {
+        let program = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let cfg = analyze(&program);
+        assert_eq!(vec![0, 15], cfg.blocks.keys().cloned().collect::<Vec<_>>());
+        assert_eq!(5, cfg.blocks[&0].instructions.len());
+        assert_eq!(
+            vec![Exit::Jump(0), Exit::Fallthrough(15)],
+            cfg.blocks[&0].exits
+        );
+        assert_eq!(vec![Exit::Halt], cfg.blocks[&15].exits);
+        assert!(cfg.blocks[&0].patched.is_empty());
+        assert_eq!(
+            "12: JZ [101] 0",
+            format!("{}", cfg.blocks[&0].instructions[4])
+        );
+    }
+
+    #[test]
+    fn test_indirect_jump() {
+        // JNZ [7] [8]; OUT 1; HALT
+        let program = vec![5, 7, 8, 104, 1, 99, 0, 1, 3];
+        let cfg = analyze(&program);
+        assert_eq!(
+            vec![Exit::Indirect, Exit::Fallthrough(3)],
+            cfg.blocks[&0].exits
+        );
+        assert!(cfg.to_dot().contains("b0 -> indirect"));
+        assert!(cfg
+            .to_json()
+            .contains("\"0\": {\"end\": 3, \"successors\": [3], \"indirect\": true"));
+    }
+
+    #[test]
+    fn test_patched_jump() {
+        // [6] = 5 + 4, then jump to [6], which started out as 0.
+        let program = vec![1101, 5, 4, 6, 1105, 1, 0, 99, 0, 104, 7, 99];
+        let cfg = analyze(&program);
+        assert_eq!(vec![0], cfg.blocks.keys().cloned().collect::<Vec<_>>());
+        assert_eq!(vec![Exit::Jump(0)], cfg.blocks[&0].exits);
+        assert_eq!(vec![6], cfg.blocks[&0].patched);
+        assert!(cfg.blocks[&0].patched_jump());
+    }
+
+    #[test]
+    fn test_invalid_code() {
+        let program = vec![1105, 1, 4, 99, 42];
+        let cfg = analyze(&program);
+        assert_eq!(vec![Exit::Jump(4)], cfg.blocks[&0].exits);
+        assert_eq!(vec![Exit::Invalid(4)], cfg.blocks[&4].exits);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operand {
+    Position(Item),
+    Immediate(Item),
+    Relative(Item),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instruction {
+    pub addr: usize,
+    pub opcode: usize,
+    pub operands: Vec<Operand>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Exit {
+    Fallthrough(usize),
+    Jump(usize),
+    // The jump target comes from memory or the relative base.
+    Indirect,
+    Halt,
+    // The block runs into something that doesn't decode as an instruction.
+    Invalid(usize),
+}
+
+#[derive(Debug)]
+pub struct Block {
+    pub start: usize,
+    pub instructions: Vec<Instruction>,
+    pub exits: Vec<Exit>,
+    // Cells in this block that some instruction writes to.
+    pub patched: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub struct Cfg {
+    pub blocks: BTreeMap<usize, Block>,
+}
+
+impl Instruction {
+    // The address just past this instruction.
+    pub fn end(&self) -> usize {
+        self.addr + self.operands.len() + 1
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self.opcode {
+            1 => "ADD",
+            2 => "MUL",
+            3 => "IN",
+            4 => "OUT",
+            5 => "JNZ",
+            6 => "JZ",
+            7 => "LT",
+            8 => "EQ",
+            9 => "ARB",
+            99 => "HALT",
+            _ => "???",
+        }
+    }
+
+    // The address this instruction writes to, if it's known without running it.
+    fn write_target(&self) -> Option<usize> {
+        match (self.opcode, self.operands.last()) {
+            (1, Some(Operand::Position(addr)))
+            | (2, Some(Operand::Position(addr)))
+            | (3, Some(Operand::Position(addr)))
+            | (7, Some(Operand::Position(addr)))
+            | (8, Some(Operand::Position(addr))) => Some(*addr as usize),
+            _ => None,
+        }
+    }
+
+    fn exits(&self) -> Vec<Exit> {
+        match self.opcode {
+            99 => vec![Exit::Halt],
+            5 | 6 => {
+                let jump = match self.operands[1] {
+                    Operand::Immediate(target) => Exit::Jump(target as usize),
+                    _ => Exit::Indirect,
+                };
+                match self.operands[0] {
+                    Operand::Immediate(cond) if (self.opcode == 5) == (cond != 0) => vec![jump],
+                    Operand::Immediate(_) => vec![Exit::Fallthrough(self.end())],
+                    _ => vec![jump, Exit::Fallthrough(self.end())],
+                }
+            }
+            _ => vec![Exit::Fallthrough(self.end())],
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Position(n) => write!(f, "[{}]", n),
+            Operand::Immediate(n) => write!(f, "{}", n),
+            Operand::Relative(n) => write!(f, "[rb{:+}]", n),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.addr, self.mnemonic())?;
+        for operand in &self.operands {
+            write!(f, " {}", operand)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn decode(memory: &IntCodeMemory, addr: usize) -> Option<Instruction> {
+    let op = *memory.get(addr)?;
+    if op < 0 {
+        return None;
+    }
+    let op = op as usize;
+    let opcode = op % 100;
+    let count = param_count(opcode)?;
+    let operands = modes(op / 100)
+        .take_checked(count)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, mode)| {
+            let raw = memory.get(addr + 1 + i)?;
+            Some(match mode {
+                ModeType::Position => Operand::Position(*raw),
+                ModeType::Immediate => Operand::Immediate(*raw),
+                ModeType::Relative => Operand::Relative(*raw),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(Instruction {
+        addr,
+        opcode,
+        operands,
+    })
+}
+
+pub fn analyze(memory: &IntCodeMemory) -> Cfg {
+    // Find every instruction reachable from the entry point.
+    let mut instructions = BTreeMap::new();
+    let mut leaders = BTreeSet::new();
+    let mut todo = vec![0];
+    leaders.insert(0);
+    while let Some(addr) = todo.pop() {
+        if instructions.contains_key(&addr) {
+            continue;
+        }
+        let instruction = match decode(memory, addr) {
+            None => continue,
+            Some(instruction) => instruction,
+        };
+        for exit in instruction.exits() {
+            match exit {
+                Exit::Fallthrough(next) => {
+                    if instruction.opcode == 5 || instruction.opcode == 6 {
+                        leaders.insert(next);
+                    }
+                    todo.push(next);
+                }
+                Exit::Jump(next) => {
+                    leaders.insert(next);
+                    todo.push(next);
+                }
+                _ => (),
+            }
+        }
+        instructions.insert(addr, instruction);
+    }
+
+    let written: BTreeSet<usize> = instructions
+        .values()
+        .filter_map(|instruction| instruction.write_target())
+        .collect();
+
+    let mut blocks = BTreeMap::new();
+    for &start in &leaders {
+        let mut block = Block {
+            start,
+            instructions: vec![],
+            exits: vec![],
+            patched: vec![],
+        };
+        let mut addr = start;
+        loop {
+            let instruction = match instructions.get(&addr) {
+                None => {
+                    block.exits = vec![Exit::Invalid(addr)];
+                    break;
+                }
+                Some(instruction) => instruction,
+            };
+            block
+                .patched
+                .extend(written.range(instruction.addr..instruction.end()));
+            block.instructions.push(instruction.clone());
+            addr = instruction.end();
+            block.exits = instruction.exits();
+            match block.exits.as_slice() {
+                [Exit::Fallthrough(next)] if !leaders.contains(next) => (),
+                _ => break,
+            }
+        }
+        blocks.insert(start, block);
+    }
+
+    Cfg { blocks }
+}
+
+impl Block {
+    // True if the block ends in a jump whose condition or target gets written to.
+    pub fn patched_jump(&self) -> bool {
+        match self.instructions.last() {
+            Some(last) if last.opcode == 5 || last.opcode == 6 => self
+                .patched
+                .iter()
+                .any(|addr| *addr > last.addr && *addr < last.end()),
+            _ => false,
+        }
+    }
+}
+
+impl Cfg {
+    pub fn to_dot(&self) -> String {
+        let mut res = String::new();
+        res.push_str("digraph intcode {\n");
+        res.push_str("  node [shape=box, fontname=\"monospace\"];\n");
+        for block in self.blocks.values() {
+            let mut label = String::new();
+            for instruction in &block.instructions {
+                label.push_str(&format!("{}\\l", instruction));
+            }
+            let style = if block.patched.is_empty() {
+                String::new()
+            } else {
+                format!(", color=red, xlabel=\"patched {:?}\"", block.patched)
+            };
+            res.push_str(&format!(
+                "  b{} [label=\"{}\"{}];\n",
+                block.start, label, style
+            ));
+            for exit in &block.exits {
+                let edge = match exit {
+                    Exit::Fallthrough(next) => format!("b{} -> b{}", block.start, next),
+                    Exit::Jump(next) if block.patched_jump() => format!(
+                        "b{} -> b{} [color=red, style=dashed, label=\"patched\"]",
+                        block.start, next
+                    ),
+                    Exit::Jump(next) => format!("b{} -> b{} [label=\"jump\"]", block.start, next),
+                    Exit::Indirect => format!(
+                        "b{} -> indirect [style=dashed, label=\"indirect\"]",
+                        block.start
+                    ),
+                    Exit::Halt => format!("b{} -> halt", block.start),
+                    Exit::Invalid(addr) => format!(
+                        "b{} -> invalid{};\n  invalid{} [shape=octagon, label=\"invalid @{}\"]",
+                        block.start, addr, addr, addr
+                    ),
+                };
+                res.push_str(&format!("  {};\n", edge));
+            }
+        }
+        if self.exits().any(|exit| exit == Exit::Indirect) {
+            res.push_str("  indirect [shape=diamond, label=\"?\"];\n");
+        }
+        if self.exits().any(|exit| exit == Exit::Halt) {
+            res.push_str("  halt [shape=doublecircle, label=\"99\"];\n");
+        }
+        res.push_str("}\n");
+        res
+    }
+
+    // {"<block start>": {"end": ..., "successors": [...], ...}, ...}
+    pub fn to_json(&self) -> String {
+        let mut entries = vec![];
+        for block in self.blocks.values() {
+            let successors: Vec<String> = block
+                .exits
+                .iter()
+                .filter_map(|exit| match exit {
+                    Exit::Fallthrough(next) | Exit::Jump(next) => Some(next.to_string()),
+                    _ => None,
+                })
+                .collect();
+            let end = block
+                .instructions
+                .last()
+                .map_or(block.start, |last| last.end());
+            let patched: Vec<String> = block.patched.iter().map(|addr| addr.to_string()).collect();
+            entries.push(format!(
+                "  \"{}\": {{\"end\": {}, \"successors\": [{}], \"indirect\": {}, \"halt\": {}, \"invalid\": {}, \"patched\": [{}], \"patched_jump\": {}}}",
+                block.start,
+                end,
+                successors.join(", "),
+                block.exits.contains(&Exit::Indirect),
+                block.exits.contains(&Exit::Halt),
+                block.exits.iter().any(|exit| matches!(exit, Exit::Invalid(_))),
+                patched.join(", "),
+                block.patched_jump(),
+            ));
+        }
+        format!("{{\n{}\n}}\n", entries.join(",\n"))
+    }
+
+    fn exits(&self) -> impl Iterator<Item = Exit> + '_ {
+        self.blocks
+            .values()
+            .flat_map(|block| block.exits.iter().cloned())
+    }
+}
@@ -0,0 +1,427 @@
+//////////
+// LOADER
+//
+// Reads programs from text or from a small binary container. The text format
+// is the usual comma-separated list, but it can be split across lines and can
+// have whitespace anywhere between values.
+//
+// Binary container (all numbers are LEB128 varints, signed ones zigzagged):
+//   "ICPG" version
+//   name-length name-bytes
+//   has-entry [entry]
+//   patch-count (addr value)*
+//   cell-count value*
+
+use super::{IntCodeMemory, Item, MAX_MEMORY_VEC};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_parse_tolerates_whitespace_and_lines() {
+        assert_eq!(Ok(vec![1, 0, 0, 3, 99]), parse("1,0,0,3,99\n"));
+        assert_eq!(
+            Ok(vec![1, 0, 0, 3, 99]),
+            parse(" 1, 0 ,0,\n3,\r\n  99 ,\n\n")
+        );
+        assert_eq!(Ok(vec![1, -2, 3, 4]), parse("1,-2\n+3\n,4"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |line, column, message: &str| {
+            Err(ParseError {
+                line,
+                column,
+                message: message.to_string(),
+            })
+        };
+        assert_eq!(err(1, 1, "empty program"), parse(" \n"));
+        assert_eq!(err(2, 3, "missing value before ','"), parse("1,2,\n3,,4"));
+        assert_eq!(err(1, 3, "expected ',' before '2'"), parse("1 2"));
+        assert_eq!(err(3, 2, "unexpected character 'x'"), parse("1,\n2,\n3x"));
+        assert_eq!(err(1, 1, "missing value before ','"), parse(",1"));
+        assert_eq!(
+            "line 1, column 3: bad value '-': invalid digit found in string",
+            format!("{}", parse("1,-,2").unwrap_err())
+        );
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let program = Program {
+            name: "day2".to_string(),
+            entry: Some(4),
+            patches: vec![(1, 12), (2, 2)],
+            memory: vec![1, 0, 0, 3, -1, 1_000_000_000_000, Item::MIN, Item::MAX, 99],
+        };
+        let bytes = program.to_bytes();
+        assert_eq!(
+            b"ICPG\x01\x04day2\x01\x04\x02\x01\x18\x02\x04\x09",
+            &bytes[..18]
+        );
+        assert_eq!(program, Program::from_bytes(&bytes).unwrap());
+        assert_eq!(program, read_from("x", &mut &bytes[..]).unwrap());
+        assert_eq!(vec![1, 12, 2, 3], program.patched_memory()[..4].to_vec());
+    }
+
+    #[test]
+    fn test_binary_errors() {
+        let bytes = Program::new("x", vec![1, 2, 3]).to_bytes();
+        let err = |bytes: &[u8]| format!("{}", Program::from_bytes(bytes).unwrap_err());
+        assert_eq!("bad program file: missing header", err(b"1,2,3"));
+        assert_eq!("bad program file: unsupported version 9", err(b"ICPG\x09"));
+        assert_eq!(
+            "bad program file: unexpected end of data",
+            err(&bytes[..bytes.len() - 1])
+        );
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert_eq!("bad program file: extra data after program", err(&extra));
+
+        let mut far = Program::new("x", vec![99]);
+        far.patches.push((MAX_MEMORY_VEC - 1, 7));
+        assert!(Program::from_bytes(&far.to_bytes()).is_ok());
+        far.entry = Some(MAX_MEMORY_VEC - 1);
+        assert!(Program::from_bytes(&far.to_bytes()).is_ok());
+        far.entry = Some(MAX_MEMORY_VEC);
+        assert_eq!(
+            "bad program file: entry 1000000 is past the end of the program (1000000 cells)",
+            err(&far.to_bytes())
+        );
+        let mut short = Program::new("x", vec![99]);
+        short.entry = Some(1);
+        assert_eq!(
+            "bad program file: entry 1 is past the end of the program (1 cells)",
+            err(&short.to_bytes())
+        );
+        far.entry = None;
+        far.patches.push((usize::MAX, 7));
+        assert_eq!(
+            format!(
+                "bad program file: patch address {} is too big (the limit is 999999)",
+                usize::MAX
+            ),
+            err(&far.to_bytes())
+        );
+    }
+
+    #[test]
+    fn test_load_path() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let text = load_path(format!("{}/data/day9/input", dir)).unwrap();
+        assert_eq!(Some(&1102), text.memory.first());
+        assert_eq!(None, text.entry);
+
+        let path = env::temp_dir().join(format!("loader-test-{}.icpg", std::process::id()));
+        text.save(&path).unwrap();
+        let binary = load_path(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(text, binary);
+
+        match load_path(format!("{}/data/day9/missing", dir)) {
+            Err(LoadError::Io(_)) => (),
+            other => panic!("expected an io error, got {:?}", other),
+        }
+    }
+}
+
+const MAGIC: &[u8] = b"ICPG";
+const VERSION: u8 = 1;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Program {
+    pub name: String,
+    pub entry: Option<usize>,
+    pub patches: Vec<(usize, Item)>,
+    pub memory: IntCodeMemory,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Parse(ParseError),
+    Binary(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{}", e),
+            LoadError::Parse(e) => write!(f, "{}", e),
+            LoadError::Binary(msg) => write!(f, "bad program file: {}", msg),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> LoadError {
+        LoadError::Io(e)
+    }
+}
+
+impl From<ParseError> for LoadError {
+    fn from(e: ParseError) -> LoadError {
+        LoadError::Parse(e)
+    }
+}
+
+impl Program {
+    pub fn new(name: &str, memory: IntCodeMemory) -> Program {
+        Program {
+            name: name.to_string(),
+            memory,
+            ..Program::default()
+        }
+    }
+
+    // The memory to start running with, with the patches applied. Patches
+    // from a file are always below MAX_MEMORY_VEC.
+    pub fn patched_memory(&self) -> IntCodeMemory {
+        let mut memory = self.memory.clone();
+        for (addr, val) in &self.patches {
+            if *addr >= memory.len() {
+                memory.resize(addr + 1, 0);
+            }
+            memory[*addr] = *val;
+        }
+        memory
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut res = MAGIC.to_vec();
+        res.push(VERSION);
+        write_varint(&mut res, self.name.len() as u64);
+        res.extend_from_slice(self.name.as_bytes());
+        match self.entry {
+            None => write_varint(&mut res, 0),
+            Some(entry) => {
+                write_varint(&mut res, 1);
+                write_varint(&mut res, entry as u64);
+            }
+        }
+        write_varint(&mut res, self.patches.len() as u64);
+        for (addr, val) in &self.patches {
+            write_varint(&mut res, *addr as u64);
+            write_varint(&mut res, zigzag(*val));
+        }
+        write_varint(&mut res, self.memory.len() as u64);
+        for val in &self.memory {
+            write_varint(&mut res, zigzag(*val));
+        }
+        res
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Program, LoadError> {
+        if !bytes.starts_with(MAGIC) {
+            return Err(LoadError::Binary("missing header".to_string()));
+        }
+        let mut bytes = bytes[MAGIC.len()..].iter();
+        match bytes.next() {
+            Some(&VERSION) => (),
+            Some(version) => {
+                return Err(LoadError::Binary(format!(
+                    "unsupported version {}",
+                    version
+                )))
+            }
+            None => return Err(LoadError::Binary("missing version".to_string())),
+        }
+
+        let name_len = read_varint(&mut bytes)? as usize;
+        let name: Vec<u8> = bytes.by_ref().take(name_len).cloned().collect();
+        if name.len() != name_len {
+            return Err(LoadError::Binary("name is cut off".to_string()));
+        }
+        let name = String::from_utf8(name).map_err(|e| LoadError::Binary(e.to_string()))?;
+        let entry = match read_varint(&mut bytes)? {
+            0 => None,
+            _ => Some(read_varint(&mut bytes)? as usize),
+        };
+        let mut patches = vec![];
+        for _ in 0..read_varint(&mut bytes)? {
+            let addr = read_varint(&mut bytes)?;
+            if addr >= MAX_MEMORY_VEC as u64 {
+                return Err(LoadError::Binary(format!(
+                    "patch address {} is too big (the limit is {})",
+                    addr,
+                    MAX_MEMORY_VEC - 1
+                )));
+            }
+            let addr = addr as usize;
+            patches.push((addr, unzigzag(read_varint(&mut bytes)?)));
+        }
+        let mut memory = vec![];
+        for _ in 0..read_varint(&mut bytes)? {
+            memory.push(unzigzag(read_varint(&mut bytes)?));
+        }
+        if bytes.next().is_some() {
+            return Err(LoadError::Binary("extra data after program".to_string()));
+        }
+        // The entry has to be somewhere in memory once the patches are in.
+        let len = patches
+            .iter()
+            .map(|(addr, _)| addr + 1)
+            .fold(memory.len(), usize::max);
+        if let Some(entry) = entry {
+            if entry >= len {
+                return Err(LoadError::Binary(format!(
+                    "entry {} is past the end of the program ({} cells)",
+                    entry, len
+                )));
+            }
+        }
+
+        Ok(Program {
+            name,
+            entry,
+            patches,
+            memory,
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+}
+
+// Parses comma-separated values. A line break also works as a separator, and
+// a trailing comma is ignored.
+pub fn parse(text: &str) -> Result<IntCodeMemory, ParseError> {
+    #[derive(PartialEq)]
+    enum State {
+        Start,
+        AfterValue,
+        AfterNewline,
+        AfterComma,
+    }
+
+    let mut res = vec![];
+    let mut state = State::Start;
+    for (i, line) in text.lines().enumerate() {
+        let err = |column: usize, message: String| ParseError {
+            line: i + 1,
+            column: column + 1,
+            message,
+        };
+        let chars: Vec<char> = line.chars().collect();
+        let mut col = 0;
+        while col < chars.len() {
+            let c = chars[col];
+            if c.is_whitespace() {
+                col += 1;
+            } else if c == ',' {
+                if state == State::Start || state == State::AfterComma {
+                    return Err(err(col, "missing value before ','".to_string()));
+                }
+                state = State::AfterComma;
+                col += 1;
+            } else if c.is_ascii_digit() || c == '-' || c == '+' {
+                if state == State::AfterValue {
+                    return Err(err(col, format!("expected ',' before '{}'", c)));
+                }
+                let start = col;
+                col += 1;
+                while col < chars.len() && chars[col].is_ascii_digit() {
+                    col += 1;
+                }
+                let token: String = chars[start..col].iter().collect();
+                let val = token
+                    .parse()
+                    .map_err(|e| err(start, format!("bad value '{}': {}", token, e)))?;
+                res.push(val);
+                state = State::AfterValue;
+            } else {
+                return Err(err(col, format!("unexpected character '{}'", c)));
+            }
+        }
+        if state == State::AfterValue {
+            state = State::AfterNewline;
+        }
+    }
+
+    if res.is_empty() {
+        return Err(ParseError {
+            line: 1,
+            column: 1,
+            message: "empty program".to_string(),
+        });
+    }
+    Ok(res)
+}
+
+// Reads either format, depending on whether the data starts with the header.
+pub fn read_from(name: &str, reader: &mut dyn Read) -> Result<Program, LoadError> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    if bytes.starts_with(MAGIC) {
+        return Program::from_bytes(&bytes);
+    }
+    let text = String::from_utf8(bytes).map_err(|e| LoadError::Binary(e.to_string()))?;
+    Ok(Program::new(name, parse(&text)?))
+}
+
+pub fn load_path<P: AsRef<Path>>(path: P) -> Result<Program, LoadError> {
+    let path = path.as_ref();
+    let mut file = fs::File::open(path)?;
+    read_from(&path.display().to_string(), &mut file)
+}
+
+pub fn load_stdin() -> Result<Program, LoadError> {
+    read_from("stdin", &mut io::stdin().lock())
+}
+
+fn zigzag(n: Item) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+fn unzigzag(n: u64) -> Item {
+    ((n >> 1) as Item) ^ -((n & 1) as Item)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn read_varint<'a>(bytes: &mut impl Iterator<Item = &'a u8>) -> Result<u64, LoadError> {
+    let mut res = 0;
+    for shift in (0..64).step_by(7) {
+        match bytes.next() {
+            None => return Err(LoadError::Binary("unexpected end of data".to_string())),
+            Some(b) => {
+                res |= ((b & 0x7f) as u64) << shift;
+                if b & 0x80 == 0 {
+                    return Ok(res);
+                }
+            }
+        }
+    }
+    Err(LoadError::Binary("number is too long".to_string()))
+}
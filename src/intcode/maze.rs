@@ -0,0 +1,322 @@
+////////
+// MAZE
+//
+// Some programs are droids in a maze. They take a movement command and say
+// whether they hit a wall, moved, or moved onto the thing they're looking
+// for. Exploring walks every reachable cell and backs up out of dead ends,
+// leaving the droid where it started.
+
+use super::{IntCodeComputer, Item, Status};
+use crate::grid::{Direction, Point, SparseGrid};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A droid in a maze drawn with '#' for walls, 'O' for the target and 'S'
+    // for the start.
+    struct FakeDroid {
+        rows: Vec<Vec<char>>,
+        pos: Point,
+    }
+
+    impl FakeDroid {
+        fn new(maze: &str) -> FakeDroid {
+            let rows: Vec<Vec<char>> = maze.lines().map(|l| l.chars().collect()).collect();
+            let mut pos = Point::ORIGIN;
+            for (y, row) in rows.iter().enumerate() {
+                for (x, c) in row.iter().enumerate() {
+                    if *c == 'S' {
+                        pos = Point::new(x as i64, y as i64);
+                    }
+                }
+            }
+            FakeDroid { rows, pos }
+        }
+    }
+
+    impl Droid for FakeDroid {
+        fn try_move(&mut self, heading: Direction) -> Result<Cell, String> {
+            let next = self.pos.step(heading);
+            match self.rows[next.y as usize][next.x as usize] {
+                '#' => Ok(Cell::Wall),
+                c => {
+                    self.pos = next;
+                    if c == 'O' {
+                        Ok(Cell::Target)
+                    } else {
+                        Ok(Cell::Open)
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_explore() {
+        let mut droid = FakeDroid::new(
+            "\
+######
+#S.###
+#.#..#
+#.O.##
+######",
+        );
+        let map = explore(&mut droid).unwrap();
+        assert_eq!(Point::new(1, 1), droid.pos);
+        assert_eq!(Some(Point::new(1, 2)), map.target());
+        assert_eq!(Some(Cell::Open), map.get(Point::new(3, 1)));
+        assert_eq!(Some(Cell::Wall), map.get(Point::new(1, 1)));
+        assert_eq!(None, map.get(Point::new(2, -1)));
+        assert_eq!(" ##   \n#S.## \n#.#..#\n#.O.# \n ###  \n", map.render());
+    }
+
+    #[test]
+    fn test_paths() {
+        let map = explore(&mut FakeDroid::new(
+            "\
+#######
+#S....#
+#.###.#
+#...#O#
+#######",
+        ))
+        .unwrap();
+        let target = map.target().unwrap();
+        assert_eq!(Point::new(4, 2), target);
+        assert_eq!(
+            Some(vec![
+                Point::new(1, 0),
+                Point::new(2, 0),
+                Point::new(3, 0),
+                Point::new(4, 0),
+                Point::new(4, 1),
+                Point::new(4, 2)
+            ]),
+            map.shortest_path(Point::ORIGIN, target)
+        );
+        assert_eq!(Some(6), map.distance(Point::ORIGIN, target));
+        assert_eq!(Some(0), map.distance(target, target));
+        assert_eq!(None, map.distance(Point::ORIGIN, Point::new(2, 1)));
+        assert_eq!(10, map.fill_time(target));
+        assert_eq!(6, map.fill_time(Point::ORIGIN));
+    }
+
+    #[test]
+    fn test_intcode_droid() {
+        // Walls all around.
+        let program = vec![3, 100, 104, 0, 1105, 1, 0];
+        let mut droid = IntCodeComputer::new("droid", program);
+        let map = explore(&mut droid).unwrap();
+        assert_eq!(None, map.target());
+        assert_eq!(0, map.fill_time(Point::ORIGIN));
+        assert_eq!(" # \n#S#\n # \n", map.render());
+    }
+
+    #[test]
+    fn test_bad_droids() {
+        let explore_program = |program: Vec<Item>| {
+            let mut droid = IntCodeComputer::new("droid", program);
+            explore(&mut droid).map(|_| ())
+        };
+        assert_eq!(
+            Err("moving from (0, 0) to (0, -1): unrecognized droid status 7".to_string()),
+            explore_program(vec![3, 100, 104, 7, 99])
+        );
+        assert_eq!(
+            Err("moving from (0, 0) to (0, -1): droid halted".to_string()),
+            explore_program(vec![3, 100, 99])
+        );
+        assert_eq!(
+            Err(
+                "moving from (0, 0) to (0, -1): droid wants input before reporting a status"
+                    .to_string()
+            ),
+            explore_program(vec![3, 100, 3, 100, 99])
+        );
+        // Moves once, then never moves again, not even back.
+        assert_eq!(
+            Err("droid couldn't back up from (0, -1)".to_string()),
+            explore_program(vec![104, 1, 3, 100, 104, 0, 1105, 1, 2])
+        );
+    }
+}
+
+const NORTH: Item = 1;
+const SOUTH: Item = 2;
+const WEST: Item = 3;
+const EAST: Item = 4;
+
+const HIT_WALL: Item = 0;
+const MOVED: Item = 1;
+const FOUND: Item = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cell {
+    Wall,
+    Open,
+    Target,
+}
+
+// A droid that stops making sense returns an error.
+pub trait Droid {
+    fn try_move(&mut self, dir: Direction) -> Result<Cell, String>;
+}
+
+impl Droid for IntCodeComputer {
+    fn try_move(&mut self, heading: Direction) -> Result<Cell, String> {
+        self.push_input(match heading {
+            Direction::Up => NORTH,
+            Direction::Down => SOUTH,
+            Direction::Left => WEST,
+            Direction::Right => EAST,
+        });
+        match self.resume() {
+            Status::Output(HIT_WALL) => Ok(Cell::Wall),
+            Status::Output(MOVED) => Ok(Cell::Open),
+            Status::Output(FOUND) => Ok(Cell::Target),
+            Status::Output(other) => Err(format!("unrecognized droid status {}", other)),
+            Status::NeedInput => Err("droid wants input before reporting a status".to_string()),
+            Status::Halted => Err("droid halted".to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Map {
+    cells: SparseGrid<Cell>,
+}
+
+// Depth first, one droid move at a time. Every cell gets tried from each side
+// at most once, and the droid walks back along its path as it finishes each
+// branch. Errors say where the droid was.
+pub fn explore(droid: &mut dyn Droid) -> Result<Map, String> {
+    let mut map = Map::default();
+    map.cells.set(Point::ORIGIN, Cell::Open);
+
+    // (where, how to get back to the previous cell, next direction to try)
+    let mut stack: Vec<(Point, Option<Direction>, usize)> = vec![(Point::ORIGIN, None, 0)];
+    while let Some(top) = stack.last_mut() {
+        let (pos, back, i) = *top;
+        if i == Direction::ALL.len() {
+            stack.pop();
+            if let Some(back) = back {
+                let moved = droid
+                    .try_move(back)
+                    .map_err(|e| format!("backing up from {}: {}", pos, e))?;
+                if moved == Cell::Wall {
+                    return Err(format!("droid couldn't back up from {}", pos));
+                }
+            }
+            continue;
+        }
+        top.2 += 1;
+
+        let dir = Direction::ALL[i];
+        let next = pos.step(dir);
+        if map.cells.contains(next) {
+            continue;
+        }
+        let cell = droid
+            .try_move(dir)
+            .map_err(|e| format!("moving from {} to {}: {}", pos, next, e))?;
+        map.cells.set(next, cell);
+        if cell != Cell::Wall {
+            stack.push((next, Some(dir.reverse()), 0));
+        }
+    }
+    Ok(map)
+}
+
+impl Map {
+    pub fn get(&self, pos: Point) -> Option<Cell> {
+        self.cells.get(pos).cloned()
+    }
+
+    pub fn target(&self) -> Option<Point> {
+        self.cells
+            .iter()
+            .find(|(_, cell)| **cell == Cell::Target)
+            .map(|(pos, _)| pos)
+    }
+
+    // The cells to step through to get from `from` to `to`, not including
+    // `from`.
+    pub fn shortest_path(&self, from: Point, to: Point) -> Option<Vec<Point>> {
+        let came_from = self.search(from);
+        if !came_from.contains_key(&to) {
+            return None;
+        }
+        let mut res = vec![];
+        let mut pos = to;
+        while pos != from {
+            res.push(pos);
+            pos = came_from[&pos];
+        }
+        res.reverse();
+        Some(res)
+    }
+
+    pub fn distance(&self, from: Point, to: Point) -> Option<usize> {
+        self.shortest_path(from, to).map(|path| path.len())
+    }
+
+    // How many steps it takes for something spreading from `from` to fill
+    // every open cell.
+    pub fn fill_time(&self, from: Point) -> usize {
+        let mut dist = HashMap::new();
+        dist.insert(from, 0);
+        let mut queue = VecDeque::new();
+        queue.push_back(from);
+        let mut res = 0;
+        while let Some(pos) = queue.pop_front() {
+            let d = dist[&pos];
+            res = d;
+            for next in self.open_neighbors(pos) {
+                if let Entry::Vacant(e) = dist.entry(next) {
+                    e.insert(d + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        res
+    }
+
+    // '#' for walls, '.' for open cells, 'O' for the target and 'S' for the
+    // start. Anything unexplored is blank.
+    pub fn render(&self) -> String {
+        self.cells.render(|pos, cell| match (cell, pos) {
+            (Some(Cell::Target), _) => 'O',
+            (_, Point::ORIGIN) => 'S',
+            (Some(Cell::Open), _) => '.',
+            (Some(Cell::Wall), _) => '#',
+            (None, _) => ' ',
+        })
+    }
+
+    // Breadth first from `from`, returning where each reachable cell was
+    // reached from.
+    fn search(&self, from: Point) -> HashMap<Point, Point> {
+        let mut came_from = HashMap::new();
+        came_from.insert(from, from);
+        let mut queue = VecDeque::new();
+        queue.push_back(from);
+        while let Some(pos) = queue.pop_front() {
+            for next in self.open_neighbors(pos) {
+                if let Entry::Vacant(e) = came_from.entry(next) {
+                    e.insert(pos);
+                    queue.push_back(next);
+                }
+            }
+        }
+        came_from
+    }
+
+    fn open_neighbors(&self, pos: Point) -> Vec<Point> {
+        pos.neighbors()
+            .filter(|next| matches!(self.get(*next), Some(Cell::Open) | Some(Cell::Target)))
+            .collect()
+    }
+}
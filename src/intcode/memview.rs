@@ -0,0 +1,242 @@
+///////////////
+// MEMORY VIEWS
+//
+// Compares memory images and draws where a program spends its memory traffic.
+
+use super::{IntCodeMemory, Item, MAX_MEMORY_VEC};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::mpsc;
+
+#[cfg(test)]
+mod tests {
+    use super::super::run_traced;
+    use super::*;
+
+    #[test]
+    fn test_diff_groups_nearby_changes() {
+        let before = vec![1, 0, 0, 3, 99, 5, 6, 7, 8, 9, 10];
+        let after = vec![1, 12, 2, 3, 99, 5, 6, 7, 8, 9, 10, 0, 0, 4];
+        let regions = diff(&before, &after);
+        assert_eq!(
+            vec![
+                DiffRegion {
+                    start: 1,
+                    before: vec![0, 0],
+                    after: vec![12, 2],
+                },
+                DiffRegion {
+                    start: 13,
+                    before: vec![0],
+                    after: vec![4],
+                },
+            ],
+            regions
+        );
+        assert_eq!("[1..=2] 0,0 => 12,2", format!("{}", regions[0]));
+
+        let regions = diff(&[1, 2, 3, 4, 5], &[9, 2, 3, 4, 9]);
+        assert_eq!(1, regions.len());
+        assert_eq!(vec![9, 2, 3, 4, 9], regions[0].after);
+    }
+
+    #[test]
+    fn test_day2_patches() {
+        let program = vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50];
+        let a = run_patched(&program, &parse_patches("1=9,2=10").unwrap(), vec![]);
+        let b = run_patched(&program, &parse_patches("1=10, 2=11").unwrap(), vec![]);
+        let regions = diff(&a, &b);
+        assert_eq!(
+            vec!["[0..=3] 3500,9,10,70 => 4500,10,11,90".to_string()],
+            regions.iter().map(|r| r.to_string()).collect::<Vec<_>>()
+        );
+        assert!(parse_patches("1").is_err());
+        assert!(parse_patches("x=1").is_err());
+        assert_eq!(
+            Err("patch address 1000000 is too big (the limit is 999999)".to_string()),
+            parse_patches("1000000=1")
+        );
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let mut counts = BTreeMap::new();
+        counts.insert(0, 100);
+        counts.insert(1, 1);
+        counts.insert(5, 10);
+        counts.insert(40, 1);
+        assert_eq!(
+            "       0 |@:   |\n       5 |+    |\n       ...\n      40 |:    |\n",
+            heatmap(&counts, 5, 1)
+        );
+        assert_eq!(
+            "       0 |@ +|\n       ...\n      36 |  :|\n",
+            heatmap(&counts, 3, 2)
+        );
+        assert_eq!(heatmap(&counts, 1, 1), heatmap(&counts, 0, 0));
+
+        let mut counts = BTreeMap::new();
+        counts.insert(1 << 34, 1);
+        assert_eq!(
+            "       ...\n17179869184 |@                                                               |\n",
+            heatmap(&counts, 64, 1)
+        );
+    }
+
+    #[test]
+    fn test_traced_counts() {
+        let program = vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50];
+        let (_, trace) = run_traced("test", program, vec![], false);
+        assert_eq!(Some(&1), trace.reads.get(&9));
+        assert_eq!(Some(&2), trace.reads.get(&3));
+        assert_eq!(Some(&1), trace.write_counts().get(&0));
+    }
+}
+
+// Unchanged cells between two changes that still get folded into one region.
+const REGION_GAP: usize = 3;
+
+// Darkest last.
+const SHADES: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+#[derive(Debug, PartialEq)]
+pub struct DiffRegion {
+    pub start: usize,
+    pub before: Vec<Item>,
+    pub after: Vec<Item>,
+}
+
+impl fmt::Display for DiffRegion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let end = self.start + self.before.len() - 1;
+        let before: Vec<String> = self.before.iter().map(|n| n.to_string()).collect();
+        let after: Vec<String> = self.after.iter().map(|n| n.to_string()).collect();
+        write!(
+            f,
+            "[{}..={}] {} => {}",
+            self.start,
+            end,
+            before.join(","),
+            after.join(",")
+        )
+    }
+}
+
+// Groups the cells that differ between two images. Cells past the end of the
+// shorter image count as 0, the same as the computer treats them.
+pub fn diff(before: &[Item], after: &[Item]) -> Vec<DiffRegion> {
+    let len = before.len().max(after.len());
+    let cell = |mem: &[Item], addr: usize| *mem.get(addr).unwrap_or(&0);
+
+    let mut res: Vec<DiffRegion> = vec![];
+    for addr in (0..len).filter(|addr| cell(before, *addr) != cell(after, *addr)) {
+        match res.last_mut() {
+            Some(region) if region.start + region.before.len() + REGION_GAP >= addr => {
+                for addr in region.start + region.before.len()..=addr {
+                    region.before.push(cell(before, addr));
+                    region.after.push(cell(after, addr));
+                }
+            }
+            _ => res.push(DiffRegion {
+                start: addr,
+                before: vec![cell(before, addr)],
+                after: vec![cell(after, addr)],
+            }),
+        }
+    }
+    res
+}
+
+// Draws access counts with `width` characters per row and `bucket` addresses
+// per character. Rows without any accesses are collapsed. A width or bucket
+// of 0 works like 1.
+pub fn heatmap(counts: &BTreeMap<usize, usize>, width: usize, bucket: usize) -> String {
+    let (width, bucket) = (width.max(1), bucket.max(1));
+    let mut buckets: BTreeMap<usize, usize> = BTreeMap::new();
+    for (addr, count) in counts {
+        *buckets.entry(addr / bucket).or_insert(0) += count;
+    }
+    let max = match buckets.values().max() {
+        None => return String::new(),
+        Some(max) => *max,
+    };
+
+    // Only rows with something in them get drawn, since traces can touch
+    // addresses far past the program.
+    let mut rows: Vec<usize> = buckets.keys().map(|i| i / width).collect();
+    rows.dedup();
+    let mut res = String::new();
+    let mut next = 0;
+    for row in rows {
+        if row > next {
+            res.push_str("       ...\n");
+        }
+        next = row + 1;
+        res.push_str(&format!("{:>8} |", row * width * bucket));
+        for i in row * width..(row + 1) * width {
+            res.push(shade(*buckets.get(&i).unwrap_or(&0), max));
+        }
+        res.push_str("|\n");
+    }
+    res
+}
+
+// Log scale, since a few cells in a hot loop tend to dwarf everything else.
+fn shade(count: usize, max: usize) -> char {
+    if count == 0 {
+        return SHADES[0];
+    }
+    let levels = (SHADES.len() - 1) as f64;
+    let level = ((count as f64).ln_1p() / (max as f64).ln_1p() * levels).ceil() as usize;
+    SHADES[level.clamp(1, SHADES.len() - 1)]
+}
+
+// Parses "1=12,2=2" into [(1, 12), (2, 2)]. Addresses have to be below
+// MAX_MEMORY_VEC, since patches go into the memory vector.
+pub fn parse_patches(s: &str) -> Result<Vec<(usize, Item)>, String> {
+    s.split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            let mut parts = part.splitn(2, '=');
+            let addr = parts.next().unwrap().trim();
+            let val = parts
+                .next()
+                .ok_or(format!("expected ADDR=VALUE, got '{}'", part))?;
+            let addr: usize = addr
+                .parse()
+                .map_err(|e| format!("bad address '{}': {}", addr, e))?;
+            if addr >= MAX_MEMORY_VEC {
+                return Err(format!(
+                    "patch address {} is too big (the limit is {})",
+                    addr,
+                    MAX_MEMORY_VEC - 1
+                ));
+            }
+            Ok((
+                addr,
+                val.trim()
+                    .parse()
+                    .map_err(|e| format!("bad value '{}': {}", val, e))?,
+            ))
+        })
+        .collect()
+}
+
+// Runs a patched copy of the program and returns its final memory.
+pub fn run_patched(
+    program: &IntCodeMemory,
+    patches: &[(usize, Item)],
+    inputs: Vec<Item>,
+) -> IntCodeMemory {
+    let mut memory = program.clone();
+    for (addr, val) in patches {
+        if *addr >= memory.len() {
+            memory.resize(addr + 1, 0);
+        }
+        memory[*addr] = *val;
+    }
+    let (in_tx, in_rx) = mpsc::channel();
+    let (out_tx, _out_rx) = mpsc::channel();
+    super::send_all(inputs, in_tx);
+    super::run("snapshot", memory, in_rx, out_tx, false)
+}
@@ -0,0 +1,279 @@
+/////////
+// ROBOT
+//
+// A robot that walks around an infinite grid. Each step it tells its brain the
+// color under it, and the brain answers with a color to paint and which way to
+// turn. Then the robot moves forward one cell.
+
+use super::{IntCodeComputer, Item, Status};
+use crate::grid::{Bounds, Direction, Point, SparseGrid};
+use std::collections::HashSet;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Script(Vec<(Item, Item)>);
+
+    impl Brain for Script {
+        fn step(&mut self, _color: Item) -> Result<Option<(Item, Item)>, String> {
+            if self.0.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(self.0.remove(0)))
+            }
+        }
+    }
+
+    #[test]
+    fn test_day11_example() {
+        let mut brain = Script(vec![(1, 0), (0, 0), (1, 0), (1, 0), (0, 1), (1, 0), (1, 0)]);
+        let mut robot = Robot::new();
+        assert_eq!(Ok(7), robot.run(&mut brain));
+        assert_eq!(6, robot.painted_count());
+        assert_eq!(Point::new(0, -1), robot.pos);
+        assert_eq!(Direction::Left, robot.heading);
+        assert_eq!("  ■\n  ■\n■■ \n", robot.render());
+    }
+
+    #[test]
+    fn test_intcode_brain() {
+        // Four times: read the color, paint white, turn left.
+        let program = vec![
+            3, 100, 104, 1, 104, 0, 1001, 101, 1, 101, 1007, 101, 4, 102, 1005, 102, 0, 99,
+        ];
+        let mut brain = IntCodeComputer::new("robot", program);
+        let mut robot = Robot::new();
+        assert_eq!(Ok(4), robot.run(&mut brain));
+        assert_eq!(4, robot.visited_count());
+        assert_eq!(4, robot.painted_count());
+        assert_eq!(Point::ORIGIN, robot.pos);
+        assert_eq!("■■\n■■\n", robot.render());
+    }
+
+    #[test]
+    fn test_custom_rules_and_hull() {
+        // Turns are in quarter turns clockwise, and moves skip a cell.
+        struct Quarters;
+        impl Rules for Quarters {
+            fn turn(&self, mut heading: Direction, turn: Item) -> Result<Direction, String> {
+                for _ in 0..turn {
+                    heading = heading.turn_right();
+                }
+                Ok(heading)
+            }
+
+            fn advance(&self, pos: Point, heading: Direction) -> Point {
+                pos.step_by(heading, 2)
+            }
+        }
+
+        // Only the starting cell is white.
+        let mut hull = SparseGrid::new();
+        hull.set(Point::ORIGIN, WHITE);
+        let mut robot = Robot::with(hull, Quarters);
+        let mut brain = Script(vec![(0, 2), (1, 3)]);
+        assert_eq!(Ok(2), robot.run(&mut brain));
+        assert_eq!(Point::new(2, 2), robot.pos);
+        assert_eq!(BLACK, robot.hull.color(Point::ORIGIN));
+        assert_eq!(WHITE, robot.hull.color(Point::new(0, 2)));
+        assert_eq!(3, robot.visited_count());
+    }
+
+    #[test]
+    fn test_bad_brain() {
+        // Paints, turns, then paints again and halts without turning.
+        let program = vec![3, 100, 104, 1, 104, 0, 3, 100, 104, 1, 99];
+        let mut brain = IntCodeComputer::new("robot", program);
+        let mut robot = Robot::new();
+        assert_eq!(
+            Err("step 2: brain halted after painting 1".to_string()),
+            robot.run(&mut brain)
+        );
+        assert_eq!(1, robot.painted_count());
+
+        // Wants a second color before it turns.
+        let program = vec![3, 100, 104, 1, 3, 100, 99];
+        let mut brain = IntCodeComputer::new("robot", program);
+        assert_eq!(
+            Err("step 1: brain wants input before it finished moving".to_string()),
+            Robot::new().run(&mut brain)
+        );
+    }
+
+    #[test]
+    fn test_bad_turn() {
+        let mut brain = Script(vec![(1, 0), (1, 7), (1, 0)]);
+        let mut robot = Robot::new();
+        assert_eq!(
+            Err("step 2: unrecognized turn 7".to_string()),
+            robot.run(&mut brain)
+        );
+        assert_eq!(Point::new(-1, 0), robot.pos);
+        assert_eq!(2, robot.painted_count());
+    }
+}
+
+pub const BLACK: Item = 0;
+pub const WHITE: Item = 1;
+
+// Same glyphs as the day 8 renderer.
+const RENDERED_BLACK: char = ' ';
+const RENDERED_WHITE: char = '■';
+
+// Whatever decides where the robot goes. Returns (color, turn), or None once
+// it's done.
+pub trait Brain {
+    fn step(&mut self, color: Item) -> Result<Option<(Item, Item)>, String>;
+}
+
+// The surface the robot paints on.
+pub trait Hull {
+    fn color(&self, pos: Point) -> Item;
+    fn paint(&mut self, pos: Point, color: Item);
+    fn bounds(&self) -> Option<Bounds>;
+}
+
+// How the brain's turn output moves the robot. A turn the rules don't
+// understand is an error.
+pub trait Rules {
+    fn turn(&self, heading: Direction, turn: Item) -> Result<Direction, String>;
+
+    fn advance(&self, pos: Point, heading: Direction) -> Point {
+        pos.step(heading)
+    }
+}
+
+// 0 turns left, 1 turns right, and the robot moves one cell.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardRules;
+
+impl Rules for StandardRules {
+    fn turn(&self, heading: Direction, turn: Item) -> Result<Direction, String> {
+        match turn {
+            0 => Ok(heading.turn_left()),
+            1 => Ok(heading.turn_right()),
+            _ => Err(format!("unrecognized turn {}", turn)),
+        }
+    }
+}
+
+// Unpainted cells are black.
+impl Hull for SparseGrid<Item> {
+    fn color(&self, pos: Point) -> Item {
+        *self.get(pos).unwrap_or(&BLACK)
+    }
+
+    fn paint(&mut self, pos: Point, color: Item) {
+        self.set(pos, color);
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        SparseGrid::bounds(self)
+    }
+}
+
+// Halting is only a clean finish between moves.
+impl Brain for IntCodeComputer {
+    fn step(&mut self, color: Item) -> Result<Option<(Item, Item)>, String> {
+        self.push_input(color);
+        let mut outputs = vec![];
+        while outputs.len() < 2 {
+            match self.resume() {
+                Status::Output(val) => outputs.push(val),
+                Status::Halted if outputs.is_empty() => return Ok(None),
+                Status::Halted => {
+                    return Err(format!("brain halted after painting {}", outputs[0]))
+                }
+                Status::NeedInput => {
+                    return Err("brain wants input before it finished moving".to_string())
+                }
+            }
+        }
+        Ok(Some((outputs[0], outputs[1])))
+    }
+}
+
+pub struct Robot<H = SparseGrid<Item>, R = StandardRules> {
+    pub pos: Point,
+    pub heading: Direction,
+    pub hull: H,
+    rules: R,
+    visited: HashSet<Point>,
+    painted: HashSet<Point>,
+}
+
+impl Robot {
+    pub fn new() -> Robot {
+        Robot::with(SparseGrid::new(), StandardRules)
+    }
+}
+
+impl Default for Robot {
+    fn default() -> Robot {
+        Robot::new()
+    }
+}
+
+impl<H: Hull, R: Rules> Robot<H, R> {
+    pub fn with(hull: H, rules: R) -> Robot<H, R> {
+        let mut visited = HashSet::new();
+        visited.insert(Point::ORIGIN);
+        Robot {
+            pos: Point::ORIGIN,
+            heading: Direction::Up,
+            hull,
+            rules,
+            visited,
+            painted: HashSet::new(),
+        }
+    }
+
+    // Returns false once the brain is done. The cell is painted even if the
+    // turn is bad, but the robot doesn't move.
+    pub fn step(&mut self, brain: &mut dyn Brain) -> Result<bool, String> {
+        match brain.step(self.hull.color(self.pos))? {
+            None => Ok(false),
+            Some((color, turn)) => {
+                self.hull.paint(self.pos, color);
+                self.painted.insert(self.pos);
+                self.heading = self.rules.turn(self.heading, turn)?;
+                self.pos = self.rules.advance(self.pos, self.heading);
+                self.visited.insert(self.pos);
+                Ok(true)
+            }
+        }
+    }
+
+    // Runs until the brain is done, and returns the number of steps taken.
+    pub fn run(&mut self, brain: &mut dyn Brain) -> Result<usize, String> {
+        let mut steps = 0;
+        while self
+            .step(brain)
+            .map_err(|e| format!("step {}: {}", steps + 1, e))?
+        {
+            steps += 1;
+        }
+        Ok(steps)
+    }
+
+    // Cells the robot has stood on, including where it started.
+    pub fn visited_count(&self) -> usize {
+        self.visited.len()
+    }
+
+    // Cells painted at least once, whatever color they ended up.
+    pub fn painted_count(&self) -> usize {
+        self.painted.len()
+    }
+
+    pub fn render(&self) -> String {
+        match self.hull.bounds() {
+            None => String::new(),
+            Some(bounds) => bounds.render(|p| match self.hull.color(p) {
+                WHITE => RENDERED_WHITE,
+                _ => RENDERED_BLACK,
+            }),
+        }
+    }
+}
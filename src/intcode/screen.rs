@@ -0,0 +1,232 @@
+//////////
+// SCREEN
+//
+// Some programs draw by outputting (x, y, tile) triples and read a joystick
+// whenever they want input. One special coordinate isn't a tile at all, it's
+// the score.
+
+use super::{IntCodeComputer, Item, Status};
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_triples() {
+        let mut screen = Screen::new();
+        for val in &[1, 2, 3, 6, 5, 4] {
+            screen.feed(*val);
+        }
+        assert_eq!(PADDLE, screen.tile(1, 2));
+        assert_eq!(BALL, screen.tile(6, 5));
+        assert_eq!(EMPTY, screen.tile(0, 0));
+        assert_eq!(Some((6, 5)), screen.find(BALL));
+        assert_eq!(None, screen.score());
+
+        screen.feed(-1);
+        screen.feed(0);
+        assert_eq!(None, screen.score());
+        screen.feed(12345);
+        assert_eq!(Some(12345), screen.score());
+        assert_eq!(2, screen.tiles.len());
+    }
+
+    #[test]
+    fn test_render() {
+        let mut screen = Screen::new().with_glyphs(".#");
+        for val in &[0, 0, 1, 2, 1, 1, 1, 1, 7, 2, 2, 0] {
+            screen.feed(*val);
+        }
+        assert_eq!("#..\n.?#\n...\n", screen.render());
+
+        let mut screen = Screen::new().with_score_at(9, 9);
+        for val in &[0, 0, 2, 9, 9, 50] {
+            screen.feed(*val);
+        }
+        assert_eq!("#\nSCORE: 50\n", screen.render());
+        assert_eq!("", Screen::new().render());
+    }
+
+    #[test]
+    fn test_headless_play() {
+        // Draws two tiles, reads the joystick, and sets the score to 10x that.
+        let program = vec![
+            104, 1, 104, 2, 104, 3, 104, 0, 104, 0, 104, 4, 3, 100, 1002, 100, 10, 100, 104, -1,
+            104, 0, 4, 100, 99,
+        ];
+        let mut computer = IntCodeComputer::new("game", program);
+        let mut seen = vec![];
+        let frame = headless(&mut computer, |screen| {
+            seen.push(screen.render());
+            7
+        });
+        assert_eq!(vec!["o \n  \n =\n".to_string()], seen);
+        assert_eq!("o \n  \n =\nSCORE: 70\n", frame);
+    }
+
+    #[test]
+    fn test_follow_ball() {
+        let mut screen = Screen::new();
+        for val in &[5, 1, BALL, 2, 3, PADDLE] {
+            screen.feed(*val);
+        }
+        assert_eq!(1, follow_ball(&screen));
+        for val in &[5, 1, EMPTY, 0, 1, BALL] {
+            screen.feed(*val);
+        }
+        assert_eq!(-1, follow_ball(&screen));
+    }
+}
+
+pub const EMPTY: Item = 0;
+pub const WALL: Item = 1;
+pub const BLOCK: Item = 2;
+pub const PADDLE: Item = 3;
+pub const BALL: Item = 4;
+
+const DEFAULT_GLYPHS: &str = " █#=o";
+const UNKNOWN_GLYPH: char = '?';
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+#[derive(Clone, Debug)]
+pub struct Screen {
+    tiles: HashMap<(Item, Item), Item>,
+    score: Option<Item>,
+    score_at: (Item, Item),
+    glyphs: Vec<char>,
+    pending: Vec<Item>,
+}
+
+impl Default for Screen {
+    fn default() -> Screen {
+        Screen {
+            tiles: HashMap::new(),
+            score: None,
+            score_at: (-1, 0),
+            glyphs: DEFAULT_GLYPHS.chars().collect(),
+            pending: vec![],
+        }
+    }
+}
+
+impl Screen {
+    pub fn new() -> Screen {
+        Screen::default()
+    }
+
+    // Treat writes to (x, y) as the score instead of a tile.
+    pub fn with_score_at(mut self, x: Item, y: Item) -> Screen {
+        self.score_at = (x, y);
+        self
+    }
+
+    // The character for each tile id, starting with 0.
+    pub fn with_glyphs(mut self, glyphs: &str) -> Screen {
+        self.glyphs = glyphs.chars().collect();
+        self
+    }
+
+    // Takes one output value. Every third one completes a triple.
+    pub fn feed(&mut self, val: Item) {
+        self.pending.push(val);
+        if let [x, y, tile] = self.pending[..] {
+            if (x, y) == self.score_at {
+                self.score = Some(tile);
+            } else {
+                self.tiles.insert((x, y), tile);
+            }
+            self.pending.clear();
+        }
+    }
+
+    pub fn tile(&self, x: Item, y: Item) -> Item {
+        *self.tiles.get(&(x, y)).unwrap_or(&EMPTY)
+    }
+
+    pub fn score(&self) -> Option<Item> {
+        self.score
+    }
+
+    pub fn count(&self, tile: Item) -> usize {
+        self.tiles.values().filter(|t| **t == tile).count()
+    }
+
+    // Where a tile is, if it's on the screen. Handy for finding the ball.
+    pub fn find(&self, tile: Item) -> Option<(Item, Item)> {
+        self.tiles
+            .iter()
+            .find(|(_, t)| **t == tile)
+            .map(|(pos, _)| *pos)
+    }
+
+    pub fn render(&self) -> String {
+        let mut res = String::new();
+        if !self.tiles.is_empty() {
+            let min_x = self.tiles.keys().map(|(x, _)| *x).min().unwrap();
+            let max_x = self.tiles.keys().map(|(x, _)| *x).max().unwrap();
+            let min_y = self.tiles.keys().map(|(_, y)| *y).min().unwrap();
+            let max_y = self.tiles.keys().map(|(_, y)| *y).max().unwrap();
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    res.push(self.glyph(self.tile(x, y)));
+                }
+                res.push('\n');
+            }
+        }
+        if let Some(score) = self.score {
+            res.push_str(&format!("SCORE: {}\n", score));
+        }
+        res
+    }
+
+    fn glyph(&self, tile: Item) -> char {
+        if tile < 0 {
+            return UNKNOWN_GLYPH;
+        }
+        *self.glyphs.get(tile as usize).unwrap_or(&UNKNOWN_GLYPH)
+    }
+}
+
+// Runs the program until it halts, asking `joystick` for input whenever the
+// program wants some. When `live` is set, the screen is redrawn in the
+// terminal before each move.
+pub fn play<F>(computer: &mut IntCodeComputer, screen: &mut Screen, mut joystick: F, live: bool)
+where
+    F: FnMut(&Screen) -> Item,
+{
+    loop {
+        match computer.resume() {
+            Status::Output(val) => screen.feed(val),
+            Status::NeedInput => {
+                if live {
+                    print!("{}{}", CLEAR_SCREEN, screen.render());
+                }
+                computer.push_input(joystick(screen));
+            }
+            Status::Halted => break,
+        }
+    }
+    if live {
+        print!("{}{}", CLEAR_SCREEN, screen.render());
+    }
+}
+
+// Plays without drawing anything and returns the final frame.
+pub fn headless<F>(computer: &mut IntCodeComputer, joystick: F) -> String
+where
+    F: FnMut(&Screen) -> Item,
+{
+    let mut screen = Screen::new();
+    play(computer, &mut screen, joystick, false);
+    screen.render()
+}
+
+// A joystick that keeps the paddle under the ball.
+pub fn follow_ball(screen: &Screen) -> Item {
+    match (screen.find(BALL), screen.find(PADDLE)) {
+        (Some((ball, _)), Some((paddle, _))) => (ball - paddle).signum(),
+        _ => 0,
+    }
+}
@@ -0,0 +1,696 @@
+///////////////////
+// SYMBOLIC INTCODE
+//
+// Runs an intcode program with some memory cells or inputs left as unknowns,
+// building expression trees instead of numbers. Straight-line arithmetic (like
+// day 2) ends up as a linear expression that can be solved directly instead of
+// trying every candidate.
+
+use super::cfg::{self, Operand};
+use super::{
+    get_mem, modes, set_mem, IntCodeComputer, IntCodeMemory, Item, ModeType, Status, MAX_MEMORY_VEC,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noun_verb() -> Vec<Unknown> {
+        vec![
+            unknown("noun", Cell::Memory(1), 0..=99),
+            unknown("verb", Cell::Memory(2), 0..=99),
+        ]
+    }
+
+    #[test]
+    fn test_day2_ex1() {
+        let program = vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50];
+        let res = run(&program, &[]).unwrap();
+        assert_eq!(Expr::Const(3500), res.memory[0]);
+    }
+
+    #[test]
+    fn test_linear_expression() {
+        // [3] = [[1]] + [[2]]; [0] = [1] * 3 + [2] + 5
+        let program = vec![1, 0, 0, 3, 1002, 1, 3, 0, 1, 0, 2, 0, 1001, 0, 5, 0, 99];
+        let res = run(&program, &noun_verb()).unwrap();
+        let linear = res.memory[0].linear().unwrap();
+        assert_eq!(5, linear.constant);
+        assert_eq!(Some(&3), linear.terms.get("noun"));
+        assert_eq!(Some(&1), linear.terms.get("verb"));
+        assert_eq!("(((3 * noun) + verb) + 5)", format!("{}", res.memory[0]));
+        assert_eq!("([noun] + [verb])", format!("{}", res.memory[3]));
+
+        assert_eq!(
+            Solution::Linear(vec![0, 19]),
+            solve(&program, &noun_verb(), 0, 24)
+        );
+        assert_eq!(Solution::NotFound, solve(&program, &noun_verb(), 0, 1000));
+    }
+
+    #[test]
+    fn test_symbolic_input() {
+        // out = input0 + input1 * 2
+        let program = vec![
+            3, 15, 3, 16, 1002, 16, 2, 16, 1, 15, 16, 17, 4, 17, 99, 0, 0, 0,
+        ];
+        let unknowns = vec![
+            unknown("a", Cell::Input(0), 0..=9),
+            unknown("b", Cell::Input(1), 0..=9),
+        ];
+        let res = run(&program, &unknowns).unwrap();
+        assert_eq!("(a + (2 * b))", format!("{}", res.outputs[0]));
+        assert_eq!(
+            Solution::Linear(vec![1, 6]),
+            solve(&program, &unknowns, 17, 13)
+        );
+    }
+
+    #[test]
+    fn test_nonlinear_falls_back_to_search() {
+        // [0] = [1] * [2]
+        let program = vec![1, 0, 0, 3, 2, 1, 2, 0, 99];
+        let res = run(&program, &noun_verb()).unwrap();
+        assert_eq!(None, res.memory[0].linear());
+        assert_eq!(
+            Solution::Search(vec![1, 42]),
+            solve(&program, &noun_verb(), 0, 42)
+        );
+    }
+
+    #[test]
+    fn test_data_dependent_jump_falls_back_to_search() {
+        // if [1] == 0 { [0] = 7 } else { [0] = [1] + [2] }
+        let program = vec![1, 0, 0, 3, 1006, 1, 12, 1, 1, 2, 0, 99, 1101, 0, 7, 0, 99];
+        assert!(run(&program, &noun_verb()).is_err());
+        assert_eq!(
+            Solution::Search(vec![0, 0]),
+            solve(&program, &noun_verb(), 0, 7)
+        );
+        assert_eq!(
+            Solution::Search(vec![1, 10]),
+            solve(&program, &noun_verb(), 0, 11)
+        );
+    }
+
+    #[test]
+    fn test_far_addresses() {
+        // [2^40] = [9] + 1; [0] = [2^40] * 2
+        let far = 1 << 40;
+        let program = vec![1001, 9, 1, far, 1002, far, 2, 0, 99, 0];
+        let unknowns = vec![unknown("x", Cell::Memory(9), 0..=9)];
+        let res = run(&program, &unknowns).unwrap();
+        assert_eq!(10, res.memory.len());
+        assert_eq!("(x + 1)", format!("{}", res.get(far as usize).unwrap()));
+        assert_eq!(Solution::Linear(vec![3]), solve(&program, &unknowns, 0, 8));
+
+        // An unknown way out in memory, and a search that has to look there.
+        let program = vec![2, far, far, 0, 99];
+        let unknowns = vec![unknown("x", Cell::Memory(far as usize), 0..=9)];
+        assert_eq!(Solution::Search(vec![7]), solve(&program, &unknowns, 0, 49));
+    }
+
+    #[test]
+    fn test_input_gaps() {
+        // out = input0 * input1, so input 1 has to be an unknown too.
+        let program = vec![3, 11, 3, 12, 2, 11, 12, 13, 4, 13, 99, 0, 0, 0];
+        let unknowns = vec![
+            unknown("a", Cell::Input(0), 0..=9),
+            unknown("c", Cell::Input(2), 0..=9),
+        ];
+        assert_eq!(Solution::NotFound, solve(&program, &unknowns, 13, 6));
+        let unknowns = vec![
+            unknown("b", Cell::Input(1), 0..=9),
+            unknown("a", Cell::Input(0), 0..=9),
+        ];
+        assert_eq!(
+            Solution::Search(vec![1, 6]),
+            solve(&program, &unknowns, 13, 6)
+        );
+    }
+
+    #[test]
+    fn test_bad_candidates() {
+        // Jumps to the unknown. 0 loops forever, and 1 and 2 run into bad
+        // opcodes. Only 3 gets to the code that leaves 7 in [20].
+        let program = vec![1105, 1, 0, 1101, 7, 0, 20, 99];
+        let unknowns = vec![unknown("to", Cell::Memory(2), 0..=3)];
+        assert_eq!(Solution::Search(vec![3]), solve(&program, &unknowns, 20, 7));
+        assert_eq!(Solution::NotFound, solve(&program, &unknowns, 20, 8));
+    }
+
+    #[test]
+    fn test_day2_input() {
+        let program = include_str!("../../data/day2/input")
+            .trim()
+            .split(',')
+            .map(|s| s.parse().unwrap())
+            .collect();
+        assert_eq!(
+            Solution::Linear(vec![12, 2]),
+            solve(&program, &noun_verb(), 0, 3_895_705)
+        );
+        assert_eq!(
+            Solution::Linear(vec![64, 17]),
+            solve(&program, &noun_verb(), 0, 19_690_720)
+        );
+    }
+}
+
+// How long a candidate gets to run before it counts as not matching. Some
+// candidates make the program loop forever.
+const MAX_CONCRETE_STEPS: usize = 1_000_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Const(Item),
+    Var(String),
+    Add(Box<Expr>, Box<Expr>),
+    Mult(Box<Expr>, Box<Expr>),
+    Lt(Box<Expr>, Box<Expr>),
+    Eq(Box<Expr>, Box<Expr>),
+    // The value at an address that depends on an unknown.
+    Load(Box<Expr>),
+}
+
+// constant + sum(coefficient * var)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Linear {
+    pub constant: Item,
+    pub terms: BTreeMap<String, Item>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cell {
+    Memory(usize),
+    Input(usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct Unknown {
+    pub name: String,
+    pub at: Cell,
+    pub range: RangeInclusive<Item>,
+}
+
+// Like the real computer, addresses past MAX_MEMORY_VEC go in a map instead
+// of growing `memory`.
+#[derive(Debug)]
+pub struct SymbolicRun {
+    pub memory: Vec<Expr>,
+    pub extra_memory: HashMap<usize, Expr>,
+    pub outputs: Vec<Expr>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Solution {
+    // Values for each unknown, in the order they were given.
+    Linear(Vec<Item>),
+    Search(Vec<Item>),
+    NotFound,
+}
+
+impl Expr {
+    pub fn var(name: &str) -> Expr {
+        Expr::Var(name.to_string())
+    }
+
+    pub fn as_const(&self) -> Option<Item> {
+        match self {
+            Expr::Const(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn linear(&self) -> Option<Linear> {
+        match self {
+            Expr::Const(n) => Some(Linear::constant(*n)),
+            Expr::Var(name) => {
+                let mut terms = BTreeMap::new();
+                terms.insert(name.to_string(), 1);
+                Some(Linear { constant: 0, terms })
+            }
+            Expr::Add(a, b) => Some(a.linear()?.add(&b.linear()?)),
+            Expr::Mult(a, b) => {
+                let a = a.linear()?;
+                let b = b.linear()?;
+                if a.terms.is_empty() {
+                    Some(b.scale(a.constant))
+                } else if b.terms.is_empty() {
+                    Some(a.scale(b.constant))
+                } else {
+                    None
+                }
+            }
+            Expr::Lt(a, b) => {
+                let diff = a.linear()?.add(&b.linear()?.scale(-1));
+                if diff.terms.is_empty() {
+                    Some(Linear::constant(if diff.constant < 0 { 1 } else { 0 }))
+                } else {
+                    None
+                }
+            }
+            Expr::Eq(a, b) => {
+                let diff = a.linear()?.add(&b.linear()?.scale(-1));
+                if diff.terms.is_empty() {
+                    Some(Linear::constant(if diff.constant == 0 { 1 } else { 0 }))
+                } else {
+                    None
+                }
+            }
+            Expr::Load(_) => None,
+        }
+    }
+
+    pub fn simplify(&self) -> Expr {
+        if let Some(linear) = self.linear() {
+            return linear.to_expr();
+        }
+        match self {
+            Expr::Add(a, b) => Expr::Add(Box::new(a.simplify()), Box::new(b.simplify())),
+            Expr::Mult(a, b) => Expr::Mult(Box::new(a.simplify()), Box::new(b.simplify())),
+            Expr::Lt(a, b) => Expr::Lt(Box::new(a.simplify()), Box::new(b.simplify())),
+            Expr::Eq(a, b) => Expr::Eq(Box::new(a.simplify()), Box::new(b.simplify())),
+            Expr::Load(addr) => Expr::Load(Box::new(addr.simplify())),
+            other => other.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Const(n) => write!(f, "{}", n),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Add(a, b) => write!(f, "({} + {})", a, b),
+            Expr::Mult(a, b) => write!(f, "({} * {})", a, b),
+            Expr::Lt(a, b) => write!(f, "({} < {})", a, b),
+            Expr::Eq(a, b) => write!(f, "({} == {})", a, b),
+            Expr::Load(addr) => write!(f, "[{}]", addr),
+        }
+    }
+}
+
+impl Linear {
+    fn constant(n: Item) -> Linear {
+        Linear {
+            constant: n,
+            terms: BTreeMap::new(),
+        }
+    }
+
+    fn add(mut self, other: &Linear) -> Linear {
+        self.constant += other.constant;
+        for (name, coefficient) in &other.terms {
+            *self.terms.entry(name.to_string()).or_insert(0) += coefficient;
+        }
+        self.terms.retain(|_, coefficient| *coefficient != 0);
+        self
+    }
+
+    fn scale(mut self, factor: Item) -> Linear {
+        self.constant *= factor;
+        for coefficient in self.terms.values_mut() {
+            *coefficient *= factor;
+        }
+        self.terms.retain(|_, coefficient| *coefficient != 0);
+        self
+    }
+
+    pub fn to_expr(&self) -> Expr {
+        let mut res = None;
+        for (name, coefficient) in &self.terms {
+            let term = match coefficient {
+                1 => Expr::var(name),
+                _ => Expr::Mult(
+                    Box::new(Expr::Const(*coefficient)),
+                    Box::new(Expr::var(name)),
+                ),
+            };
+            res = Some(match res {
+                None => term,
+                Some(prev) => Expr::Add(Box::new(prev), Box::new(term)),
+            });
+        }
+        match res {
+            None => Expr::Const(self.constant),
+            Some(res) if self.constant == 0 => res,
+            Some(res) => Expr::Add(Box::new(res), Box::new(Expr::Const(self.constant))),
+        }
+    }
+}
+
+pub fn unknown(name: &str, at: Cell, range: RangeInclusive<Item>) -> Unknown {
+    Unknown {
+        name: name.to_string(),
+        at,
+        range,
+    }
+}
+
+struct SymbolicComputer<'a> {
+    memory: Vec<Expr>,
+    extra_memory: HashMap<usize, Expr>,
+    unknowns: &'a [Unknown],
+    outputs: Vec<Expr>,
+    inputs_read: usize,
+    pc: usize,
+    relative_base: Item,
+}
+
+// Runs the program with each unknown replaced by a variable. Fails when the
+// program does something that depends on an unknown value, like jumping on it
+// or writing to an address it picks.
+pub fn run(program: &IntCodeMemory, unknowns: &[Unknown]) -> Result<SymbolicRun, String> {
+    let mut computer = SymbolicComputer {
+        memory: program.iter().map(|n| Expr::Const(*n)).collect(),
+        extra_memory: HashMap::new(),
+        unknowns,
+        outputs: vec![],
+        inputs_read: 0,
+        pc: 0,
+        relative_base: 0,
+    };
+    for unknown in unknowns {
+        if let Cell::Memory(addr) = unknown.at {
+            computer.set(addr, Expr::var(&unknown.name));
+        }
+    }
+
+    loop {
+        let op = computer.concrete(computer.pc, "opcode")?;
+        let opcode = op % 100;
+        let mut modes = modes(op as usize / 100);
+        match opcode {
+            1 | 2 | 7 | 8 => {
+                let arg1 = computer.read(computer.pc + 1, &mut modes)?;
+                let arg2 = computer.read(computer.pc + 2, &mut modes)?;
+                let (arg1, arg2) = (Box::new(arg1), Box::new(arg2));
+                let val = match opcode {
+                    1 => Expr::Add(arg1, arg2),
+                    2 => Expr::Mult(arg1, arg2),
+                    7 => Expr::Lt(arg1, arg2),
+                    _ => Expr::Eq(arg1, arg2),
+                };
+                computer.write(computer.pc + 3, &mut modes, val.simplify())?;
+                computer.pc += 4;
+            }
+            3 => {
+                let val = computer.input()?;
+                computer.write(computer.pc + 1, &mut modes, val)?;
+                computer.pc += 2;
+            }
+            4 => {
+                let val = computer.read(computer.pc + 1, &mut modes)?;
+                computer.outputs.push(val);
+                computer.pc += 2;
+            }
+            5 | 6 => {
+                let cond = computer.read(computer.pc + 1, &mut modes)?;
+                let cond = match cond.as_const() {
+                    Some(cond) => cond,
+                    None => return Err(format!("jump at {} depends on {}", computer.pc, cond)),
+                };
+                let target = computer.read(computer.pc + 2, &mut modes)?;
+                let target = match target.as_const() {
+                    Some(target) => target,
+                    None => return Err(format!("jump at {} goes to {}", computer.pc, target)),
+                };
+                if (opcode == 5) == (cond != 0) {
+                    computer.pc = target as usize;
+                } else {
+                    computer.pc += 3;
+                }
+            }
+            9 => {
+                let arg = computer.read(computer.pc + 1, &mut modes)?;
+                match arg.as_const() {
+                    Some(arg) => computer.relative_base += arg,
+                    None => {
+                        return Err(format!(
+                            "relative base at {} depends on {}",
+                            computer.pc, arg
+                        ))
+                    }
+                }
+                computer.pc += 2;
+            }
+            99 => break,
+            _ => return Err(format!("bad opcode {} at {}", op, computer.pc)),
+        }
+    }
+
+    Ok(SymbolicRun {
+        memory: computer.memory,
+        extra_memory: computer.extra_memory,
+        outputs: computer.outputs,
+    })
+}
+
+impl SymbolicRun {
+    // What's at `addr` when the program halted, or None if nothing was ever
+    // there.
+    pub fn get(&self, addr: usize) -> Option<&Expr> {
+        if addr < MAX_MEMORY_VEC {
+            self.memory.get(addr)
+        } else {
+            self.extra_memory.get(&addr)
+        }
+    }
+}
+
+impl SymbolicComputer<'_> {
+    fn get(&self, addr: usize) -> Expr {
+        let val = if addr < MAX_MEMORY_VEC {
+            self.memory.get(addr)
+        } else {
+            self.extra_memory.get(&addr)
+        };
+        val.cloned().unwrap_or(Expr::Const(0))
+    }
+
+    fn set(&mut self, addr: usize, val: Expr) {
+        if addr >= MAX_MEMORY_VEC {
+            self.extra_memory.insert(addr, val);
+            return;
+        }
+        if addr >= self.memory.len() {
+            self.memory.resize(addr + 1, Expr::Const(0));
+        }
+        self.memory[addr] = val;
+    }
+
+    fn concrete(&self, addr: usize, what: &str) -> Result<Item, String> {
+        let val = self.get(addr);
+        val.as_const()
+            .ok_or_else(|| format!("{} at {} depends on {}", what, addr, val))
+    }
+
+    fn read(
+        &self,
+        raw_addr: usize,
+        modes: &mut impl Iterator<Item = ModeType>,
+    ) -> Result<Expr, String> {
+        let raw = self.get(raw_addr);
+        let addr = match (modes.next().unwrap(), raw.as_const()) {
+            (ModeType::Immediate, _) => return Ok(raw),
+            (ModeType::Position, Some(raw)) => raw as usize,
+            (ModeType::Relative, Some(raw)) => (raw + self.relative_base) as usize,
+            (ModeType::Position, None) => return Ok(Expr::Load(Box::new(raw))),
+            (ModeType::Relative, None) => {
+                let addr = Expr::Add(Box::new(raw), Box::new(Expr::Const(self.relative_base)));
+                return Ok(Expr::Load(Box::new(addr.simplify())));
+            }
+        };
+        Ok(self.get(addr))
+    }
+
+    fn write(
+        &mut self,
+        raw_addr: usize,
+        modes: &mut impl Iterator<Item = ModeType>,
+        val: Expr,
+    ) -> Result<(), String> {
+        let addr = match modes.next().unwrap() {
+            ModeType::Position => self.concrete(raw_addr, "address")? as usize,
+            ModeType::Relative => {
+                (self.concrete(raw_addr, "address")? + self.relative_base) as usize
+            }
+            ModeType::Immediate => {
+                return Err(format!("illegal write to immediate addr at {}", self.pc))
+            }
+        };
+        self.set(addr, val);
+        Ok(())
+    }
+
+    fn input(&mut self) -> Result<Expr, String> {
+        let index = self.inputs_read;
+        self.inputs_read += 1;
+        self.unknowns
+            .iter()
+            .find(|unknown| unknown.at == Cell::Input(index))
+            .map(|unknown| Expr::var(&unknown.name))
+            .ok_or_else(|| format!("input {} is not an unknown", index))
+    }
+}
+
+// Finds values for the unknowns that leave `target` in memory at `result_addr`
+// when the program halts. Uses the symbolic result when it's linear, and falls
+// back to trying every combination of values otherwise.
+pub fn solve(
+    program: &IntCodeMemory,
+    unknowns: &[Unknown],
+    result_addr: usize,
+    target: Item,
+) -> Solution {
+    let linear = run(program, unknowns)
+        .ok()
+        .and_then(|res| res.get(result_addr).and_then(|expr| expr.linear()));
+    match linear {
+        Some(linear) => match solve_linear(&linear, unknowns, target) {
+            Some(values) => Solution::Linear(values),
+            None => Solution::NotFound,
+        },
+        None => match search(program, unknowns, result_addr, target) {
+            Some(values) => Solution::Search(values),
+            None => Solution::NotFound,
+        },
+    }
+}
+
+fn solve_linear(linear: &Linear, unknowns: &[Unknown], target: Item) -> Option<Vec<Item>> {
+    // The last unknown with a coefficient is solved for; the rest are enumerated.
+    let solve_for = unknowns
+        .iter()
+        .rposition(|unknown| linear.terms.contains_key(&unknown.name));
+    let solve_for = match solve_for {
+        None => {
+            return if linear.constant == target {
+                Some(
+                    unknowns
+                        .iter()
+                        .map(|unknown| *unknown.range.start())
+                        .collect(),
+                )
+            } else {
+                None
+            }
+        }
+        Some(i) => i,
+    };
+    let coefficient = linear.terms[&unknowns[solve_for].name];
+
+    let mut found = None;
+    each_assignment(unknowns, solve_for, &mut vec![], &mut |values| {
+        let sum = unknowns
+            .iter()
+            .zip(values.iter())
+            .map(|(unknown, val)| linear.terms.get(&unknown.name).unwrap_or(&0) * val)
+            .sum::<Item>();
+        let rest = target - linear.constant - sum;
+        if rest % coefficient == 0 && unknowns[solve_for].range.contains(&(rest / coefficient)) {
+            let mut values = values.to_vec();
+            values[solve_for] = rest / coefficient;
+            found = Some(values);
+            true
+        } else {
+            false
+        }
+    });
+    found
+}
+
+fn search(
+    program: &IntCodeMemory,
+    unknowns: &[Unknown],
+    result_addr: usize,
+    target: Item,
+) -> Option<Vec<Item>> {
+    let mut found = None;
+    each_assignment(unknowns, unknowns.len(), &mut vec![], &mut |values| {
+        if run_concrete(program, unknowns, values, result_addr) == Some(target) {
+            found = Some(values.to_vec());
+            true
+        } else {
+            false
+        }
+    });
+    found
+}
+
+// The value at `result_addr` when the program halts. None if it reads an input
+// that isn't one of the unknowns, runs into something the computer can't run,
+// or doesn't halt within MAX_CONCRETE_STEPS.
+fn run_concrete(
+    program: &IntCodeMemory,
+    unknowns: &[Unknown],
+    values: &[Item],
+    result_addr: usize,
+) -> Option<Item> {
+    let mut computer = IntCodeComputer::new("search", program.clone());
+    let mut inputs = HashMap::new();
+    for (unknown, val) in unknowns.iter().zip(values.iter()) {
+        match unknown.at {
+            Cell::Memory(addr) => set_mem(&mut computer, addr, *val),
+            Cell::Input(index) => {
+                inputs.insert(index, *val);
+            }
+        }
+    }
+
+    let mut inputs_read = 0;
+    for _ in 0..MAX_CONCRETE_STEPS {
+        if !runnable(&computer) {
+            return None;
+        }
+        match computer.step() {
+            None | Some(Status::Output(_)) => (),
+            Some(Status::NeedInput) => {
+                computer.push_input(*inputs.get(&inputs_read)?);
+                inputs_read += 1;
+            }
+            Some(Status::Halted) => return Some(get_mem(&mut computer, result_addr)),
+        }
+    }
+    None
+}
+
+// Whether the computer can run the instruction at its pc without panicking.
+fn runnable(computer: &IntCodeComputer) -> bool {
+    match cfg::decode(&computer.memory, computer.pc) {
+        None => false,
+        Some(instruction) => !matches!(
+            (instruction.opcode, instruction.operands.last()),
+            (1 | 2 | 3 | 7 | 8, Some(Operand::Immediate(_)))
+        ),
+    }
+}
+
+// Calls `f` with every combination of values for the unknowns, holding the
+// one at `skip` at 0, until `f` returns true.
+fn each_assignment<F>(unknowns: &[Unknown], skip: usize, values: &mut Vec<Item>, f: &mut F) -> bool
+where
+    F: FnMut(&[Item]) -> bool,
+{
+    if values.len() == unknowns.len() {
+        return f(values);
+    }
+    let range = if values.len() == skip {
+        0..=0
+    } else {
+        unknowns[values.len()].range.clone()
+    };
+    for val in range {
+        values.push(val);
+        let done = each_assignment(unknowns, skip, values, f);
+        values.pop();
+        if done {
+            return true;
+        }
+    }
+    false
+}
@@ -0,0 +1,135 @@
+/////////
+// TRACE
+//
+// Records what a program did while it ran, so we can tell whether it ever
+// rewrites its own instructions. Programs that don't are safe to cache or
+// compile ahead of time.
+
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+mod tests {
+    use super::super::run_traced;
+    use super::*;
+
+    #[test]
+    fn test_day9_quine_is_not_self_modifying() {
+        let program = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let (outputs, trace) = run_traced("test", program.clone(), vec![], false);
+        assert_eq!(program, outputs);
+        assert_eq!(Some(&(4, 16)), trace.executed.get(&4));
+        assert_eq!(Some(&16), trace.writes.get(&(4, 100)));
+        assert!(!trace.is_self_modifying());
+    }
+
+    #[test]
+    fn test_patched_jump() {
+        // [6] = 5 + 4, then jump to [6], which started out as 0.
+        let program = vec![1101, 5, 4, 6, 1105, 1, 0, 99, 0, 104, 7, 99];
+        let (outputs, trace) = run_traced("test", program, vec![], false);
+        assert_eq!(vec![7], outputs);
+        assert_eq!(
+            vec![Modification {
+                writer_pc: 0,
+                target: 4,
+                addr: 6,
+                count: 1
+            }],
+            trace.self_modifications()
+        );
+    }
+
+    #[test]
+    fn test_counter_in_instruction() {
+        // Outputs the immediate at [1], then bumps it, until it reaches 3.
+        let program = vec![104, 0, 1001, 1, 1, 1, 1007, 1, 3, 14, 1005, 14, 0, 99, 0];
+        let (outputs, trace) = run_traced("test", program, vec![], false);
+        assert_eq!(vec![0, 1, 2], outputs);
+        assert_eq!(
+            vec![Modification {
+                writer_pc: 2,
+                target: 0,
+                addr: 1,
+                count: 3
+            }],
+            trace.self_modifications()
+        );
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Trace {
+    // instruction address -> (instruction length, times executed)
+    pub executed: BTreeMap<usize, (usize, usize)>,
+    // (pc of the writing instruction, address written) -> times written
+    pub writes: BTreeMap<(usize, usize), usize>,
+    // address -> times read, including reads of instruction parameters
+    pub reads: BTreeMap<usize, usize>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Modification {
+    // The instruction that did the writing.
+    pub writer_pc: usize,
+    // The instruction that was written to.
+    pub target: usize,
+    // The cell that was written, either target or one of its parameters.
+    pub addr: usize,
+    pub count: usize,
+}
+
+impl Trace {
+    pub(super) fn exec(&mut self, pc: usize, len: usize) {
+        let entry = self.executed.entry(pc).or_insert((len, 0));
+        entry.1 += 1;
+    }
+
+    pub(super) fn write(&mut self, pc: usize, addr: usize) {
+        *self.writes.entry((pc, addr)).or_insert(0) += 1;
+    }
+
+    pub(super) fn read(&mut self, addr: usize) {
+        *self.reads.entry(addr).or_insert(0) += 1;
+    }
+
+    // address -> times written, by any instruction
+    pub fn write_counts(&self) -> BTreeMap<usize, usize> {
+        let mut res = BTreeMap::new();
+        for ((_, addr), count) in &self.writes {
+            *res.entry(*addr).or_insert(0) += count;
+        }
+        res
+    }
+
+    // Every write that landed on a cell belonging to an instruction that was
+    // executed at some point during the run, before or after the write.
+    pub fn self_modifications(&self) -> Vec<Modification> {
+        let mut res = vec![];
+        for ((writer_pc, addr), count) in &self.writes {
+            for target in self.instructions_at(*addr) {
+                res.push(Modification {
+                    writer_pc: *writer_pc,
+                    target,
+                    addr: *addr,
+                    count: *count,
+                });
+            }
+        }
+        res
+    }
+
+    pub fn is_self_modifying(&self) -> bool {
+        !self.self_modifications().is_empty()
+    }
+
+    fn instructions_at(&self, addr: usize) -> Vec<usize> {
+        // Instructions are at most 4 cells long.
+        self.executed
+            .range(addr.saturating_sub(3)..=addr)
+            .filter(|(start, (len, _))| *start + len > addr)
+            .map(|(start, _)| *start)
+            .collect()
+    }
+}
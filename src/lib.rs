@@ -1 +1,7 @@
+pub mod fuel;
+pub mod grid;
 pub mod intcode;
+pub mod orbit;
+pub mod password;
+pub mod sif;
+pub mod wires;
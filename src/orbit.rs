@@ -0,0 +1,770 @@
+/////////
+// ORBIT
+//
+// Orbit maps, where each line like "A)B" says that B orbits A. A valid map
+// is a tree: everything orbits exactly one thing, except for a single root
+// (usually COM) that doesn't orbit anything.
+//
+// Names are only stored once. Everything else refers to objects by their
+// ids, which index into plain vectors.
+
+pub mod export;
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(name: &str) -> OrbitMap {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        load(format!("{}/data/day6/{}", dir, name)).unwrap()
+    }
+
+    fn errors(text: &str) -> Vec<String> {
+        parse(text.lines())
+            .unwrap_err()
+            .iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let map = example("ex1.txt");
+        assert_eq!("COM", map.root());
+        assert_eq!(12, map.len());
+        assert_eq!(42, map.total_orbits());
+        assert_eq!(Some(3), map.depth("D"));
+        assert_eq!(Some(7), map.depth("L"));
+        assert_eq!(Some(0), map.depth("COM"));
+        assert_eq!(None, map.depth("YOU"));
+        assert_eq!(Some("C"), map.center("D"));
+        assert_eq!(None, map.center("COM"));
+        assert_eq!(Some(vec!["E", "I"]), map.satellites("D"));
+        assert_eq!(Some(vec!["C", "B", "COM"]), map.path_to_root("D"));
+        assert_eq!(Some(vec![]), map.path_to_root("COM"));
+
+        // Ids follow the order that names show up in the file.
+        assert_eq!(
+            vec!["COM", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L"],
+            map.names().collect::<Vec<_>>()
+        );
+        let (d, l) = (map.id("D").unwrap(), map.id("L").unwrap());
+        assert_eq!("L", map.name(l));
+        assert_eq!(Some(0), map.center_id(1));
+        assert_eq!(&[4, 8], map.satellite_ids(d));
+        assert_eq!(d, map.lca_id(l, map.id("I").unwrap()));
+        assert_eq!(4, map.distance_ids(d, l));
+        assert_eq!(vec![d, 4, 9, 10, l], map.path_ids(d, l));
+        assert_eq!(12, map.subtree_size_ids()[map.root_id() as usize]);
+
+        let walk = map.walk();
+        assert_eq!(Some(&"COM"), walk.first());
+        for (i, name) in walk.iter().enumerate() {
+            if let Some(center) = map.center(name) {
+                assert!(walk[..i].contains(&center), "{} before {}", name, center);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let map = example("ex1.txt");
+        assert_eq!(
+            Stats {
+                objects: 12,
+                direct: 11,
+                indirect: 31,
+                max_depth: 7,
+                depths: vec![1, 1, 2, 2, 2, 2, 1, 1],
+            },
+            map.stats()
+        );
+        let sizes = map.subtree_sizes();
+        assert_eq!(12, sizes.len());
+        assert_eq!(12, sizes["COM"]);
+        assert_eq!(8, sizes["C"]);
+        assert_eq!(2, sizes["G"]);
+        assert_eq!(1, sizes["L"]);
+    }
+
+    #[test]
+    fn test_other_root() {
+        // Lines can come in any order, and the root doesn't have to be COM.
+        let map = parse(vec!["B)C", "", " A ) B "]).unwrap();
+        assert_eq!("A", map.root());
+        assert_eq!(3, map.total_orbits());
+    }
+
+    #[test]
+    fn test_bad_lines() {
+        assert_eq!(
+            vec![
+                "line 1 ('AB'): expected CENTER)SATELLITE",
+                "line 2 ('A)'): missing name",
+                "line 3 ('A)B)C'): too many ')'",
+                "line 4 ('A)A'): A can't orbit itself",
+                "line 6 ('C)B'): B already orbits A (line 5)",
+                "line 7 ('A)B'): B already orbits A (line 5)",
+            ],
+            errors("AB\nA)\nA)B)C\nA)A\nA)B\nC)B\nA)B\n")
+        );
+        assert_eq!(vec!["no orbits"], errors("\n\n"));
+    }
+
+    #[test]
+    fn test_structure_errors() {
+        assert_eq!(
+            vec![
+                "line 2 ('X)Y'): X doesn't orbit anything, but COM is already the root (line 3)",
+                "line 4 ('B)C'): cycle: C -> B -> D -> C (lines 4, 6, 5)",
+                "line 7 ('C)E'): E orbits C, which is in a cycle",
+            ],
+            errors("A)Z\nX)Y\nCOM)A\nB)C\nC)D\nD)B\nC)E\nE)F\n")
+        );
+    }
+
+    #[test]
+    fn test_queries() {
+        let map = example("ex2.txt");
+        assert_eq!(Some("D"), map.lca("YOU", "SAN"));
+        assert_eq!(Some("E"), map.lca("L", "E"));
+        assert_eq!(Some("E"), map.lca("E", "L"));
+        assert_eq!(Some("COM"), map.lca("COM", "H"));
+        assert_eq!(Some("K"), map.lca("K", "K"));
+        assert_eq!(None, map.lca("YOU", "SANTA"));
+
+        assert_eq!(Some(6), map.distance("YOU", "SAN"));
+        assert_eq!(Some(3), map.distance("L", "E"));
+        assert_eq!(Some(7), map.distance("COM", "L"));
+        assert_eq!(Some(0), map.distance("B", "B"));
+        assert_eq!(Some(4), map.transfers("YOU", "SAN"));
+        assert_eq!(None, map.transfers("COM", "SAN"));
+
+        assert_eq!(
+            Some(vec!["YOU", "K", "J", "E", "D", "I", "SAN"]),
+            map.path("YOU", "SAN")
+        );
+        assert_eq!(Some(vec!["E", "J", "K", "L"]), map.path("E", "L"));
+        assert_eq!(Some(vec!["H", "G", "B", "C"]), map.path("H", "C"));
+        assert_eq!(Some(vec!["B"]), map.path("B", "B"));
+        assert_eq!(None, map.path("B", "Q"));
+    }
+
+    #[test]
+    fn test_day6_input() {
+        let map = example("input");
+        assert_eq!(234446, map.total_orbits());
+        assert_eq!(Some(385), map.transfers("YOU", "SAN"));
+        assert_eq!(map.distance("YOU", "SAN"), Some(385 + 2));
+        assert_eq!(Some(385 + 3), map.path("YOU", "SAN").map(|p| p.len()));
+    }
+
+    #[test]
+    fn test_deep_chain() {
+        // Nothing should walk the map recursively, or this would overflow the
+        // stack.
+        let n = 1_000_000;
+        let name = |i: usize| format!("N{}", i);
+        let lines: Vec<String> = (1..n)
+            .rev()
+            .map(|i| format!("{}){}", name(i - 1), name(i)))
+            .collect();
+        let map = parse(&lines).unwrap();
+        let (first, last) = (name(0), name(n - 1));
+        assert_eq!(first, map.root());
+        assert_eq!(n * (n - 1) / 2, map.total_orbits());
+        assert_eq!(Some(n - 1), map.depth(&last));
+        assert_eq!(Some(name(n / 2).as_str()), map.lca(&last, &name(n / 2)));
+        assert_eq!(Some(n - 1), map.distance(&first, &last));
+        assert_eq!(Some(n), map.path(&last, &first).map(|p| p.len()));
+        assert_eq!(Some(n - 1), map.path_to_root(&last).map(|p| p.len()));
+
+        let stats = map.stats();
+        assert_eq!(n - 1, stats.max_depth);
+        assert!(stats.depths.iter().all(|d| *d == 1));
+        assert_eq!(n, map.subtree_sizes()[first.as_str()]);
+
+        let mut cycle = lines;
+        cycle.push(format!("{}){}", last, first));
+        let errors = parse(&cycle).unwrap_err();
+        assert_eq!(1, errors.len());
+        assert!(errors[0].message.starts_with("cycle: N"));
+    }
+}
+
+// The root that the puzzle uses. It wins if a map has more than one root.
+pub const COM: &str = "COM";
+
+// Objects are numbered from 0, in the order they first show up in the map.
+pub type Id = u32;
+
+// `line` counts lines, or entries for a map read from JSON, from 1. It's 0
+// when there isn't anything to blame.
+#[derive(Debug, PartialEq)]
+pub struct OrbitError {
+    pub place: Place,
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+// What an error's `line` counts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Place {
+    Line,
+    Entry,
+}
+
+// The only way to get one is to parse it, so there's always a root.
+#[derive(Clone, Debug)]
+pub struct OrbitMap {
+    root: Id,
+    names: Vec<String>,
+    ids: HashMap<String, Id>,
+    centers: Vec<Option<Id>>,
+    satellites: Vec<Vec<Id>>,
+    // How many things each object orbits, directly and indirectly.
+    depths: Vec<usize>,
+}
+
+// Counts for a whole map. Every object but the root orbits its center
+// directly, and everything further toward the root indirectly.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub objects: usize,
+    pub direct: usize,
+    pub indirect: usize,
+    pub max_depth: usize,
+    // How many objects there are at each depth, starting with the root.
+    pub depths: Vec<usize>,
+}
+
+// One "A)B" to add to a map, and where it came from. `text` is only used
+// in errors about lines.
+struct Orbit<'a> {
+    at: usize,
+    text: &'a str,
+    center: &'a str,
+    satellite: &'a str,
+}
+
+// Whether walking toward the root from an object gets there.
+#[derive(Clone, Copy, PartialEq)]
+enum Reach {
+    Walking,
+    Root,
+    Lost,
+}
+
+impl fmt::Display for OrbitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.line, self.place) {
+            (0, _) => write!(f, "{}", self.message),
+            (_, Place::Line) => {
+                write!(f, "line {} ('{}'): {}", self.line, self.text, self.message)
+            }
+            (_, Place::Entry) => write!(f, "entry {}: {}", self.line, self.message),
+        }
+    }
+}
+
+impl Place {
+    fn one(self) -> &'static str {
+        match self {
+            Place::Line => "line",
+            Place::Entry => "entry",
+        }
+    }
+
+    fn many(self) -> &'static str {
+        match self {
+            Place::Line => "lines",
+            Place::Entry => "entries",
+        }
+    }
+}
+
+// Splits "A)B" into ("A", "B").
+fn parse_orbit(text: &str) -> Result<(&str, &str), String> {
+    let i = match text.find(')') {
+        None => return Err("expected CENTER)SATELLITE".to_string()),
+        Some(i) => i,
+    };
+    let (center, satellite) = (text[..i].trim(), text[i + 1..].trim());
+    if satellite.contains(')') {
+        return Err("too many ')'".to_string());
+    }
+    Ok((center, satellite))
+}
+
+// Reads a map, one orbit per line. Blank lines are skipped. Everything wrong
+// with the map is returned, sorted by line: bad lines, objects with two
+// centers, cycles, objects cut off from the root by a cycle, and extra roots.
+pub fn parse<I, S>(lines: I) -> Result<OrbitMap, Vec<OrbitError>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let texts: Vec<String> = lines
+        .into_iter()
+        .map(|line| line.as_ref().trim().to_string())
+        .collect();
+    let mut orbits = vec![];
+    let mut errors = vec![];
+    for (i, text) in texts.iter().enumerate() {
+        if text.is_empty() {
+            continue;
+        }
+        match parse_orbit(text) {
+            Ok((center, satellite)) => orbits.push(Orbit {
+                at: i + 1,
+                text,
+                center,
+                satellite,
+            }),
+            Err(message) => errors.push(OrbitError {
+                place: Place::Line,
+                line: i + 1,
+                text: text.to_string(),
+                message,
+            }),
+        }
+    }
+    build(&orbits, Place::Line, errors)
+}
+
+// Checks the orbits and puts them together into a map. `errors` has anything
+// the caller already found wrong, and the orbits that go with those errors
+// should already be left out.
+fn build(
+    orbits: &[Orbit],
+    place: Place,
+    mut errors: Vec<OrbitError>,
+) -> Result<OrbitMap, Vec<OrbitError>> {
+    let mut map = OrbitMap::empty();
+    // The orbit that says what each object orbits. For roots, it's the first
+    // orbit that mentions them.
+    let mut sources: Vec<usize> = vec![];
+    let err = |k: usize, message: String| OrbitError {
+        place,
+        line: orbits[k].at,
+        text: orbits[k].text.to_string(),
+        message,
+    };
+    let at = |k: usize| orbits[k].at;
+
+    for (k, orbit) in orbits.iter().enumerate() {
+        let (center, satellite) = (orbit.center, orbit.satellite);
+        if center.is_empty() || satellite.is_empty() {
+            errors.push(err(k, "missing name".to_string()));
+            continue;
+        }
+        if center == satellite {
+            errors.push(err(k, format!("{} can't orbit itself", center)));
+            continue;
+        }
+        if let Some(id) = map.id(satellite) {
+            if let Some(old) = map.centers[id as usize] {
+                errors.push(err(
+                    k,
+                    format!(
+                        "{} already orbits {} ({} {})",
+                        satellite,
+                        map.name(old),
+                        place.one(),
+                        at(sources[id as usize])
+                    ),
+                ));
+                continue;
+            }
+        }
+        let center = map.intern(center);
+        let satellite = map.intern(satellite);
+        sources.resize(map.len(), k);
+        map.satellites[center as usize].push(satellite);
+        map.centers[satellite as usize] = Some(center);
+        sources[satellite as usize] = k;
+    }
+
+    let mut roots: Vec<(usize, Id)> = map
+        .ids()
+        .filter(|id| map.centers[*id as usize].is_none())
+        .map(|id| (sources[id as usize], id))
+        .collect();
+    roots.sort_unstable();
+    if let Some(i) = roots.iter().position(|(_, id)| map.name(*id) == COM) {
+        let com = roots.remove(i);
+        roots.insert(0, com);
+    }
+    if let Some((root_source, root)) = roots.first() {
+        for (source, id) in &roots[1..] {
+            errors.push(err(
+                *source,
+                format!(
+                    "{} doesn't orbit anything, but {} is already the root ({} {})",
+                    map.name(*id),
+                    map.name(*root),
+                    place.one(),
+                    at(*root_source)
+                ),
+            ));
+        }
+    }
+
+    // Anything that can't be reached from a root is in a cycle or orbits
+    // one, and finding out which takes another look at everything.
+    let mut reached = 0;
+    let mut stack: Vec<(Id, usize)> = roots.iter().map(|(_, id)| (*id, 0)).collect();
+    while let Some((id, depth)) = stack.pop() {
+        map.depths[id as usize] = depth;
+        reached += 1;
+        for sat in &map.satellites[id as usize] {
+            stack.push((*sat, depth + 1));
+        }
+    }
+    let (cycles, orphans) = if reached < map.len() {
+        find_lost(&map, &sources)
+    } else {
+        (vec![], vec![])
+    };
+    for cycle in cycles {
+        let places: Vec<String> = cycle
+            .iter()
+            .map(|id| at(sources[*id as usize]).to_string())
+            .collect();
+        let mut names: Vec<&str> = cycle.iter().map(|id| map.name(*id)).collect();
+        names.push(map.name(cycle[0]));
+        errors.push(err(
+            sources[cycle[0] as usize],
+            format!(
+                "cycle: {} ({} {})",
+                names.join(" -> "),
+                place.many(),
+                places.join(", ")
+            ),
+        ));
+    }
+    for id in orphans {
+        errors.push(err(
+            sources[id as usize],
+            format!(
+                "{} orbits {}, which is in a cycle",
+                map.name(id),
+                map.name(map.centers[id as usize].unwrap())
+            ),
+        ));
+    }
+
+    if errors.is_empty() && roots.is_empty() {
+        errors.push(OrbitError {
+            place,
+            line: 0,
+            text: String::new(),
+            message: "no orbits".to_string(),
+        });
+    }
+    if !errors.is_empty() {
+        errors.sort_by_key(|e| e.line);
+        return Err(errors);
+    }
+
+    map.root = roots[0].1;
+    Ok(map)
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<OrbitMap, Vec<OrbitError>> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse(text.lines()),
+        Err(e) => Err(vec![OrbitError {
+            place: Place::Line,
+            line: 0,
+            text: String::new(),
+            message: e.to_string(),
+        }]),
+    }
+}
+
+// Finds the objects that can't get to a root by following their centers.
+// Each cycle is listed from its earliest orbit, and each object after that is
+// the center of the one before it. Orphans are the objects that aren't in a
+// cycle but orbit something that is. `lines` says where each object's orbit
+// is, in the same order as the input.
+fn find_lost(map: &OrbitMap, lines: &[usize]) -> (Vec<Vec<Id>>, Vec<Id>) {
+    let mut ids: Vec<Id> = map.ids().collect();
+    ids.sort_by_key(|id| lines[*id as usize]);
+
+    let mut reach: Vec<Option<Reach>> = vec![None; map.len()];
+    let mut cycles = vec![];
+    for id in ids {
+        let mut walk = vec![];
+        let mut next = Some(id);
+        let found = loop {
+            let id = match next {
+                None => break Reach::Root,
+                Some(id) => id,
+            };
+            match reach[id as usize] {
+                Some(Reach::Walking) => {
+                    let start = walk.iter().position(|i| *i == id).unwrap();
+                    let mut cycle = walk[start..].to_vec();
+                    let first = (0..cycle.len())
+                        .min_by_key(|i| lines[cycle[*i] as usize])
+                        .unwrap();
+                    cycle.rotate_left(first);
+                    cycles.push(cycle);
+                    break Reach::Lost;
+                }
+                Some(found) => break found,
+                None => {
+                    reach[id as usize] = Some(Reach::Walking);
+                    walk.push(id);
+                    next = map.centers[id as usize];
+                }
+            }
+        };
+        for id in walk {
+            reach[id as usize] = Some(found);
+        }
+    }
+
+    let mut in_cycle = vec![false; map.len()];
+    for id in cycles.iter().flatten() {
+        in_cycle[*id as usize] = true;
+    }
+    let mut orphans: Vec<Id> = map
+        .ids()
+        .filter(|id| reach[*id as usize] == Some(Reach::Lost) && !in_cycle[*id as usize])
+        .filter(|id| in_cycle[map.centers[*id as usize].unwrap() as usize])
+        .collect();
+    orphans.sort_by_key(|id| lines[*id as usize]);
+    (cycles, orphans)
+}
+
+impl OrbitMap {
+    // No objects yet, and no root until parse picks one.
+    fn empty() -> OrbitMap {
+        OrbitMap {
+            root: 0,
+            names: vec![],
+            ids: HashMap::new(),
+            centers: vec![],
+            satellites: vec![],
+            depths: vec![],
+        }
+    }
+
+    // The id for a name, adding the name if it's new.
+    fn intern(&mut self, name: &str) -> Id {
+        if let Some(id) = self.id(name) {
+            return id;
+        }
+        let id = Id::try_from(self.len()).expect("too many objects");
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        self.centers.push(None);
+        self.satellites.push(vec![]);
+        self.depths.push(0);
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<Id> {
+        self.ids.get(name).cloned()
+    }
+
+    pub fn name(&self, id: Id) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = Id> {
+        0..self.len() as Id
+    }
+
+    pub fn root(&self) -> &str {
+        self.name(self.root)
+    }
+
+    pub fn root_id(&self) -> Id {
+        self.root
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.ids.contains_key(name)
+    }
+
+    // In id order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|s| s.as_str())
+    }
+
+    pub fn center_id(&self, id: Id) -> Option<Id> {
+        self.centers[id as usize]
+    }
+
+    pub fn satellite_ids(&self, id: Id) -> &[Id] {
+        &self.satellites[id as usize]
+    }
+
+    pub fn depth_of(&self, id: Id) -> usize {
+        self.depths[id as usize]
+    }
+
+    pub fn center(&self, name: &str) -> Option<&str> {
+        Some(self.name(self.center_id(self.id(name)?)?))
+    }
+
+    pub fn satellites(&self, name: &str) -> Option<Vec<&str>> {
+        let id = self.id(name)?;
+        Some(
+            self.satellite_ids(id)
+                .iter()
+                .map(|sat| self.name(*sat))
+                .collect(),
+        )
+    }
+
+    // How many things an object orbits, directly and indirectly.
+    pub fn depth(&self, name: &str) -> Option<usize> {
+        Some(self.depth_of(self.id(name)?))
+    }
+
+    // Everything an object orbits, starting with its center and ending with
+    // the root.
+    pub fn path_to_root(&self, name: &str) -> Option<Vec<&str>> {
+        let mut res = vec![];
+        let mut next = self.center_id(self.id(name)?);
+        while let Some(id) = next {
+            res.push(self.name(id));
+            next = self.center_id(id);
+        }
+        Some(res)
+    }
+
+    // The number of direct and indirect orbits in the whole map.
+    pub fn total_orbits(&self) -> usize {
+        self.depths.iter().sum()
+    }
+
+    // Every object, starting with the root. Each object comes after its
+    // center.
+    pub fn walk_ids(&self) -> Vec<Id> {
+        let mut res = Vec::with_capacity(self.len());
+        let mut stack = vec![self.root];
+        while let Some(id) = stack.pop() {
+            res.push(id);
+            stack.extend(self.satellite_ids(id));
+        }
+        res
+    }
+
+    pub fn walk(&self) -> Vec<&str> {
+        self.walk_ids()
+            .into_iter()
+            .map(|id| self.name(id))
+            .collect()
+    }
+
+    pub fn stats(&self) -> Stats {
+        let mut res = Stats {
+            objects: self.len(),
+            direct: self.len() - 1,
+            ..Stats::default()
+        };
+        for depth in &self.depths {
+            if *depth >= res.depths.len() {
+                res.depths.resize(depth + 1, 0);
+            }
+            res.depths[*depth] += 1;
+        }
+        res.max_depth = res.depths.len() - 1;
+        res.indirect = self.total_orbits() - res.direct;
+        res
+    }
+
+    // How many objects are in the subtree under each object, counting the
+    // object itself. Indexed by id.
+    pub fn subtree_size_ids(&self) -> Vec<usize> {
+        let mut res = vec![1; self.len()];
+        for id in self.walk_ids().into_iter().rev() {
+            if let Some(center) = self.center_id(id) {
+                res[center as usize] += res[id as usize];
+            }
+        }
+        res
+    }
+
+    pub fn subtree_sizes(&self) -> HashMap<&str, usize> {
+        self.names().zip(self.subtree_size_ids()).collect()
+    }
+
+    // The deepest object that both objects are or orbit. Starting at the
+    // same depth, the two walks toward the root meet there.
+    pub fn lca_id(&self, mut a: Id, mut b: Id) -> Id {
+        while self.depth_of(a) > self.depth_of(b) {
+            a = self.center_id(a).unwrap();
+        }
+        while self.depth_of(b) > self.depth_of(a) {
+            b = self.center_id(b).unwrap();
+        }
+        while a != b {
+            a = self.center_id(a).unwrap();
+            b = self.center_id(b).unwrap();
+        }
+        a
+    }
+
+    pub fn lca(&self, a: &str, b: &str) -> Option<&str> {
+        Some(self.name(self.lca_id(self.id(a)?, self.id(b)?)))
+    }
+
+    pub fn distance_ids(&self, a: Id, b: Id) -> usize {
+        let lca = self.lca_id(a, b);
+        self.depth_of(a) + self.depth_of(b) - 2 * self.depth_of(lca)
+    }
+
+    // How many orbits apart two objects are.
+    pub fn distance(&self, a: &str, b: &str) -> Option<usize> {
+        Some(self.distance_ids(self.id(a)?, self.id(b)?))
+    }
+
+    pub fn path_ids(&self, a: Id, b: Id) -> Vec<Id> {
+        let lca = self.lca_id(a, b);
+        let climb = |mut id: Id| {
+            let mut res = vec![];
+            while id != lca {
+                res.push(id);
+                id = self.center_id(id).unwrap();
+            }
+            res
+        };
+        let mut res = climb(a);
+        res.push(lca);
+        res.extend(climb(b).into_iter().rev());
+        res
+    }
+
+    // The objects from `a` to `b`, including both of them.
+    pub fn path(&self, a: &str, b: &str) -> Option<Vec<&str>> {
+        let path = self.path_ids(self.id(a)?, self.id(b)?);
+        Some(path.into_iter().map(|id| self.name(id)).collect())
+    }
+
+    // How many orbital transfers it takes to go from orbiting what `from`
+    // orbits to orbiting what `to` orbits.
+    pub fn transfers(&self, from: &str, to: &str) -> Option<usize> {
+        self.distance(self.center(from)?, self.center(to)?)
+    }
+}
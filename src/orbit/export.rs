@@ -0,0 +1,342 @@
+//////////
+// EXPORT
+//
+// Orbit maps in other formats: Graphviz DOT for looking at, nested JSON that
+// follows the tree, and flat JSON with each object's center. Flat JSON can
+// be read back in, too.
+
+use super::{build, Id, Orbit, OrbitError, OrbitMap, Place};
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+#[cfg(test)]
+mod tests {
+    use super::super::{load, parse};
+    use super::*;
+
+    fn example() -> OrbitMap {
+        parse("COM)B\nB)C\nB)D\nD)YOU\nC)SAN\n".lines()).unwrap()
+    }
+
+    #[test]
+    fn test_dot() {
+        let map = example();
+        let path = map.path("D", "C").unwrap();
+        let plain = to_dot(&map, &[]);
+        assert!(plain.starts_with(
+            "digraph orbits {\n  node [fontname=\"monospace\"];\n  \"COM\" [shape=doublecircle];\n"
+        ));
+        assert!(!plain.contains("red"));
+        let dot = to_dot(&map, &path);
+        assert!(dot.contains("  \"B\" [color=red, fontcolor=red];\n"));
+        assert!(dot.contains("  \"B\" -> \"C\" [color=red, penwidth=2];\n"));
+        assert!(dot.contains("  \"B\" -> \"D\" [color=red, penwidth=2];\n"));
+        assert!(dot.contains("  \"COM\" -> \"B\";\n"));
+        assert!(dot.contains("  \"D\" -> \"YOU\";\n"));
+        assert_eq!(5, dot.matches(" -> ").count());
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_json() {
+        let map = example();
+        assert_eq!(
+            "{\"COM\":{\"B\":{\"C\":{\"SAN\":{}},\"D\":{\"YOU\":{}}}}}\n",
+            to_json(&map)
+        );
+        assert_eq!(
+            "{\"B\": \"COM\", \"C\": \"B\", \"D\": \"B\", \"SAN\": \"C\", \"YOU\": \"D\"}\n",
+            to_flat_json(&map)
+        );
+        assert_eq!("\"a\\\"b\\\\c\\n\\u0001\"", json_string("a\"b\\c\n\u{1}"));
+    }
+
+    #[test]
+    fn test_from_flat_json() {
+        let map =
+            from_flat_json(" { \"B\" : \"COM\",\"C\":\"B\\u0041\" ,\n\"BA\": \"B\" } ").unwrap();
+        assert_eq!(Some("BA"), map.center("C"));
+        assert_eq!(Some(2), map.depth("BA"));
+
+        let errors = |text: &str| -> Vec<String> {
+            from_flat_json(text)
+                .unwrap_err()
+                .iter()
+                .map(|e| e.to_string())
+                .collect()
+        };
+        assert_eq!(vec!["byte 0: expected '{', not '['"], errors("[]"));
+        assert_eq!(
+            vec!["byte 9: expected ',' or '}', not '\"'"],
+            errors("{\"B\":\"A\" \"C\":\"B\"}")
+        );
+        assert_eq!(
+            vec!["byte 10: extra text after the object"],
+            errors("{\"B\":\"A\"} x")
+        );
+        assert_eq!(vec!["byte 5: expected '\"', not '1'"], errors("{\"B\":1}"));
+        assert_eq!(vec!["unterminated string"], errors("{\"B"));
+        assert_eq!(
+            vec!["byte 6: expected ':', but the JSON ended"],
+            errors("{\"B\"  ")
+        );
+        assert_eq!(vec!["no orbits"], errors("{}"));
+        assert_eq!(
+            vec!["entry 2: B already orbits A (entry 1)"],
+            errors("{\"B\": \"A\", \"B\": \"C\"}")
+        );
+        assert_eq!(
+            vec![
+                "entry 1: cycle: B -> C -> B (entries 1, 2)",
+                "entry 4: E can't orbit itself",
+                "entry 5: missing name",
+            ],
+            errors("{\"B\": \"C\", \"C\": \"B\", \"A\": \"D\", \"E\": \"E\", \"\": \"A\"}")
+        );
+    }
+
+    #[test]
+    fn test_odd_names() {
+        // Names that the text format can't hold still work in JSON.
+        let json = "{\" C \": \"B)x\", \"B)x\": \"COM\"}\n";
+        let map = from_flat_json(json).unwrap();
+        assert_eq!(Some("B)x"), map.center(" C "));
+        assert_eq!(Some(2), map.depth(" C "));
+        assert_eq!(json, to_flat_json(&map));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let map = load(format!("{}/data/day6/input", dir)).unwrap();
+        let copy = from_flat_json(&to_flat_json(&map)).unwrap();
+        assert_eq!(map.len(), copy.len());
+        assert_eq!(map.stats(), copy.stats());
+        for name in map.names() {
+            assert_eq!(map.center(name), copy.center(name));
+        }
+
+        // The first line of the old output is a flat JSON dump of the input.
+        let output = std::fs::read_to_string(format!("{}/data/day6/output", dir)).unwrap();
+        let old = from_flat_json(output.lines().next().unwrap()).unwrap();
+        assert_eq!(to_flat_json(&map), to_flat_json(&old));
+
+        let json = to_json(&map);
+        assert_eq!(map.len(), json.matches(":{").count());
+        assert!(json.starts_with("{\"COM\":{"));
+    }
+}
+
+type Chars<'a> = Peekable<CharIndices<'a>>;
+
+// Edges go from each center to its satellites, and the root is circled
+// twice. The objects in `path` and the edges between them are red.
+pub fn to_dot(map: &OrbitMap, path: &[&str]) -> String {
+    let on_path: HashSet<(&str, &str)> = path.windows(2).map(|w| (w[0], w[1])).collect();
+    let mut res = String::new();
+    res.push_str("digraph orbits {\n");
+    res.push_str("  node [fontname=\"monospace\"];\n");
+    writeln!(res, "  {} [shape=doublecircle];", quote(map.root())).unwrap();
+    for name in path {
+        writeln!(res, "  {} [color=red, fontcolor=red];", quote(name)).unwrap();
+    }
+    for name in map.walk() {
+        if let Some(center) = map.center(name) {
+            let style = if on_path.contains(&(center, name)) || on_path.contains(&(name, center)) {
+                " [color=red, penwidth=2]"
+            } else {
+                ""
+            };
+            writeln!(res, "  {} -> {}{};", quote(center), quote(name), style).unwrap();
+        }
+    }
+    res.push_str("}\n");
+    res
+}
+
+// Each object is a key whose value has its satellites, like
+// {"COM":{"B":{"C":{}},"D":{}}}. Satellites are sorted by name. There's no
+// indentation, because it would be most of the output for a deep map.
+pub fn to_json(map: &OrbitMap) -> String {
+    // Sorted backwards, so popping gives them in order.
+    let satellites = |id: Id| {
+        let mut res = map.satellite_ids(id).to_vec();
+        res.sort_unstable_by(|a, b| map.name(*b).cmp(map.name(*a)));
+        res
+    };
+
+    let mut res = String::from("{");
+    let mut stack = vec![vec![map.root_id()]];
+    let mut first = true;
+    while let Some(names) = stack.last_mut() {
+        match names.pop() {
+            None => {
+                stack.pop();
+                res.push('}');
+                first = false;
+            }
+            Some(id) => {
+                if !first {
+                    res.push(',');
+                }
+                write!(res, "{}:{{", json_string(map.name(id))).unwrap();
+                stack.push(satellites(id));
+                first = true;
+            }
+        }
+    }
+    res.push('\n');
+    res
+}
+
+// {"satellite": "center", ...}, sorted by satellite.
+pub fn to_flat_json(map: &OrbitMap) -> String {
+    let mut names: Vec<&str> = map.names().filter(|name| *name != map.root()).collect();
+    names.sort_unstable();
+    let entries: Vec<String> = names
+        .iter()
+        .map(|name| {
+            format!(
+                "{}: {}",
+                json_string(name),
+                json_string(map.center(name).unwrap())
+            )
+        })
+        .collect();
+    format!("{{{}}}\n", entries.join(", "))
+}
+
+// Reads what to_flat_json writes. The map is checked like any other, but the
+// errors count entries in the JSON object instead of lines. Names are used
+// just as they are, so they can have ')' or spaces in them.
+pub fn from_flat_json(text: &str) -> Result<OrbitMap, Vec<OrbitError>> {
+    let entries = parse_flat_json(text).map_err(|message| {
+        vec![OrbitError {
+            place: Place::Entry,
+            line: 0,
+            text: String::new(),
+            message,
+        }]
+    })?;
+    let orbits: Vec<Orbit> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, (satellite, center))| Orbit {
+            at: i + 1,
+            text: "",
+            center,
+            satellite,
+        })
+        .collect();
+    build(&orbits, Place::Entry, vec![])
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn json_string(text: &str) -> String {
+    let mut res = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(res, "\\u{:04x}", c as u32).unwrap(),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+// Only understands an object whose keys and values are all strings.
+fn parse_flat_json(text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut chars = text.char_indices().peekable();
+    let mut res = vec![];
+    expect(&mut chars, text, '{')?;
+    skip_whitespace(&mut chars);
+    if let Some((_, '}')) = chars.peek() {
+        chars.next();
+    } else {
+        loop {
+            let key = json_str(&mut chars, text)?;
+            expect(&mut chars, text, ':')?;
+            let value = json_str(&mut chars, text)?;
+            res.push((key, value));
+            skip_whitespace(&mut chars);
+            match chars.next() {
+                Some((_, ',')) => continue,
+                Some((_, '}')) => break,
+                Some((i, c)) => {
+                    return Err(format!("byte {}: expected ',' or '}}', not '{}'", i, c))
+                }
+                None => return Err("unexpected end of JSON".to_string()),
+            }
+        }
+    }
+    skip_whitespace(&mut chars);
+    match chars.next() {
+        None => Ok(res),
+        Some((i, _)) => Err(format!("byte {}: extra text after the object", i)),
+    }
+}
+
+fn skip_whitespace(chars: &mut Chars) {
+    while let Some((_, c)) = chars.peek() {
+        if !c.is_whitespace() {
+            break;
+        }
+        chars.next();
+    }
+}
+
+fn expect(chars: &mut Chars, text: &str, expected: char) -> Result<(), String> {
+    skip_whitespace(chars);
+    match chars.next() {
+        Some((_, c)) if c == expected => Ok(()),
+        Some((i, c)) => Err(format!("byte {}: expected '{}', not '{}'", i, expected, c)),
+        None => Err(format!(
+            "byte {}: expected '{}', but the JSON ended",
+            text.len(),
+            expected
+        )),
+    }
+}
+
+fn json_str(chars: &mut Chars, text: &str) -> Result<String, String> {
+    expect(chars, text, '"')?;
+    let mut res = String::new();
+    loop {
+        let (i, c) = chars.next().ok_or("unterminated string")?;
+        match c {
+            '"' => return Ok(res),
+            '\\' => {
+                let (_, c) = chars.next().ok_or("unterminated string")?;
+                res.push(match c {
+                    '"' | '\\' | '/' => c,
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'u' => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or(format!("byte {}: unsupported escape '\\u{}'", i, hex))?
+                    }
+                    c => return Err(format!("byte {}: bad escape '\\{}'", i, c)),
+                });
+            }
+            c if (c as u32) < 0x20 => {
+                return Err(format!("byte {}: control character in string", i))
+            }
+            c => res.push(c),
+        }
+    }
+}
@@ -0,0 +1,418 @@
+////////////
+// PASSWORD
+//
+// Rules about the digits of a number, and named sets of them. A number
+// passes a rule set when it passes every rule in it.
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(vec![1, 2, 3, 4, 5, 6], digits(123456));
+        assert_eq!(vec![0], digits(0));
+        assert_eq!(vec![1, 0, 0], digits(100));
+        assert_eq!(vec![2, 1, 3, 2], runs(&[4, 4, 5, 6, 6, 6, 7, 7]));
+    }
+
+    #[test]
+    fn test_puzzle_examples() {
+        let rules = puzzle_rules();
+        let check = |num| {
+            rules
+                .iter()
+                .map(|set| set.is_candidate(num))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![true, false], check(111111));
+        assert_eq!(vec![false, false], check(223450));
+        assert_eq!(vec![false, false], check(123789));
+        assert_eq!(vec![true, true], check(112233));
+        assert_eq!(vec![true, false], check(123444));
+        assert_eq!(vec![true, true], check(111122));
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let set = RuleSet::new("short and even")
+            .with(Rule::NonDecreasing)
+            .with(Rule::custom(|d| d.len() <= 3))
+            .with(Rule::custom(|d| d.last().unwrap() % 2 == 0));
+        assert!(set.is_candidate(8));
+        assert!(set.is_candidate(338));
+        assert!(!set.is_candidate(3388));
+        assert!(!set.is_candidate(337));
+        assert!(!set.is_candidate(332));
+        assert!(RuleSet::new("anything").is_candidate(987));
+    }
+
+    #[test]
+    fn test_count() {
+        // The range includes both ends.
+        assert_eq!(vec![10, 1], count(111110..=111122, &puzzle_rules()));
+        assert_eq!(vec![9, 9], count(0..=99, &puzzle_rules()));
+    }
+
+    #[test]
+    fn test_day4_input() {
+        assert_eq!(vec![1694, 1148], count(156218..=652527, &puzzle_rules()));
+        assert_eq!(
+            Counts {
+                non_decreasing: 1723,
+                pair: 1694,
+                exact_pair: 1148
+            },
+            count_fast(156218..=652527)
+        );
+    }
+
+    fn count_slow(range: RangeInclusive<u64>) -> Counts {
+        let non_decreasing = RuleSet::new("non-decreasing").with(Rule::NonDecreasing);
+        let counts = count(
+            range,
+            &[
+                non_decreasing,
+                puzzle_rules().remove(0),
+                puzzle_rules().remove(1),
+            ],
+        );
+        Counts {
+            non_decreasing: counts[0] as u64,
+            pair: counts[1] as u64,
+            exact_pair: counts[2] as u64,
+        }
+    }
+
+    #[test]
+    fn test_count_fast_edges() {
+        assert_eq!(count_slow(0..=0), count_fast(0..=0));
+        assert_eq!(count_slow(0..=9), count_fast(0..=9));
+        assert_eq!(count_slow(10..=10), count_fast(10..=10));
+        assert_eq!(count_slow(0..=1000), count_fast(0..=1000));
+        assert_eq!(count_slow(111111..=111111), count_fast(111111..=111111));
+        assert_eq!(count_slow(99..=12345), count_fast(99..=12345));
+        let (min, max) = (10, 9);
+        assert_eq!(Counts::default(), count_fast(min..=max));
+    }
+
+    #[test]
+    fn test_count_fast_random_ranges() {
+        // A small LCG, so the ranges are the same on every run.
+        let mut seed: u64 = 2019;
+        let mut next = |limit: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % limit
+        };
+        for _ in 0..200 {
+            let min = next(1_000_000);
+            let max = min + next(5_000);
+            assert_eq!(
+                count_slow(min..=max),
+                count_fast(min..=max),
+                "{}..={}",
+                min,
+                max
+            );
+        }
+    }
+
+    // The original day4 check, for six-digit numbers and the second part's
+    // rules.
+    fn is_candidate(num: u32) -> bool {
+        let d1 = num / 100_000;
+        let d2 = (num / 10_000) % 10;
+        let d3 = (num / 1_000) % 10;
+        let d4 = (num / 100) % 10;
+        let d5 = (num / 10) % 10;
+        let d6 = num % 10;
+
+        let increases_only = d1 <= d2 && d2 <= d3 && d3 <= d4 && d4 <= d5 && d5 <= d6;
+        let has_double = (d1 == d2 && d2 != d3)
+            || (d1 != d2 && d2 == d3 && d3 != d4)
+            || (d2 != d3 && d3 == d4 && d4 != d5)
+            || (d3 != d4 && d4 == d5 && d5 != d6)
+            || (d4 != d5 && d5 == d6);
+
+        increases_only && has_double
+    }
+
+    #[test]
+    fn test_count_fast_original() {
+        let original = |min: u32, max: u32| (min..=max).filter(|n| is_candidate(*n)).count() as u64;
+        let mut seed: u64 = 4;
+        let mut next = |limit: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % limit
+        };
+        for _ in 0..200 {
+            let min = 100_000 + next(900_000);
+            let max = (min + next(50_000)).min(999_999);
+            assert_eq!(
+                original(min as u32, max as u32),
+                count_fast(min..=max).exact_pair,
+                "{}..={}",
+                min,
+                max
+            );
+        }
+        assert_eq!(
+            original(100_000, 999_999),
+            count_fast(100_000..=999_999).exact_pair
+        );
+    }
+
+    #[test]
+    fn test_count_fast_long_numbers() {
+        // Every non-decreasing number with up to 19 digits, which is C(28, 9)
+        // strings of 19 digits where leading zeros are allowed.
+        let all = count_fast(0..=9_999_999_999_999_999_999);
+        assert_eq!(6_906_900, all.non_decreasing);
+        assert!(all.exact_pair < all.pair);
+        let longest = count_fast(1_000_000_000_000_000_000..=9_999_999_999_999_999_999);
+        assert_eq!(
+            all.non_decreasing - count_fast(0..=999_999_999_999_999_999).non_decreasing,
+            longest.non_decreasing
+        );
+        assert!(count_fast(0..=u64::MAX).non_decreasing > all.non_decreasing);
+    }
+}
+
+pub type Predicate = Box<dyn Fn(&[u8]) -> bool>;
+
+pub enum Rule {
+    // Each digit is at least as big as the one before it.
+    NonDecreasing,
+    // Two adjacent digits are the same.
+    HasPair,
+    // Two adjacent digits are the same, and they're not part of a longer run.
+    HasExactPair,
+    Custom(Predicate),
+}
+
+// How many numbers are non-decreasing, and how many of those also pass each
+// of the pair rules.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Counts {
+    pub non_decreasing: u64,
+    pub pair: u64,
+    pub exact_pair: u64,
+}
+
+pub struct RuleSet {
+    pub name: String,
+    pub rules: Vec<Rule>,
+}
+
+// The decimal digits of a number, most significant first.
+pub fn digits(mut num: u64) -> Vec<u8> {
+    let mut res = vec![(num % 10) as u8];
+    num /= 10;
+    while num > 0 {
+        res.push((num % 10) as u8);
+        num /= 10;
+    }
+    res.reverse();
+    res
+}
+
+// The lengths of the runs of equal digits.
+fn runs(digits: &[u8]) -> Vec<usize> {
+    let mut res: Vec<usize> = vec![];
+    for (i, d) in digits.iter().enumerate() {
+        if i > 0 && digits[i - 1] == *d {
+            *res.last_mut().unwrap() += 1;
+        } else {
+            res.push(1);
+        }
+    }
+    res
+}
+
+impl Rule {
+    pub fn custom<F: Fn(&[u8]) -> bool + 'static>(f: F) -> Rule {
+        Rule::Custom(Box::new(f))
+    }
+
+    pub fn check(&self, digits: &[u8]) -> bool {
+        match self {
+            Rule::NonDecreasing => digits.windows(2).all(|w| w[0] <= w[1]),
+            Rule::HasPair => runs(digits).iter().any(|n| *n >= 2),
+            Rule::HasExactPair => runs(digits).contains(&2),
+            Rule::Custom(f) => f(digits),
+        }
+    }
+}
+
+impl RuleSet {
+    pub fn new(name: &str) -> RuleSet {
+        RuleSet {
+            name: name.to_string(),
+            rules: vec![],
+        }
+    }
+
+    pub fn with(mut self, rule: Rule) -> RuleSet {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn check(&self, digits: &[u8]) -> bool {
+        self.rules.iter().all(|rule| rule.check(digits))
+    }
+
+    pub fn is_candidate(&self, num: u64) -> bool {
+        self.check(&digits(num))
+    }
+}
+
+// The two sets of rules from the puzzle.
+pub fn puzzle_rules() -> Vec<RuleSet> {
+    vec![
+        RuleSet::new("PART 1")
+            .with(Rule::NonDecreasing)
+            .with(Rule::HasPair),
+        RuleSet::new("PART 2")
+            .with(Rule::NonDecreasing)
+            .with(Rule::HasExactPair),
+    ]
+}
+
+// How many numbers in the range pass each rule set, by checking every one.
+pub fn count(range: RangeInclusive<u64>, sets: &[RuleSet]) -> Vec<usize> {
+    let mut res = vec![0; sets.len()];
+    for num in range {
+        let digits = digits(num);
+        for (i, set) in sets.iter().enumerate() {
+            if set.check(&digits) {
+                res[i] += 1;
+            }
+        }
+    }
+    res
+}
+
+// Like counting with the puzzle rules, but without looking at every number.
+// The count for a range is the count up to max minus the count below min.
+pub fn count_fast(range: RangeInclusive<u64>) -> Counts {
+    let (min, max) = range.into_inner();
+    if min > max {
+        return Counts::default();
+    }
+    let mut res = count_upto(max);
+    if min > 0 {
+        let below = count_upto(min - 1);
+        res.non_decreasing -= below.non_decreasing;
+        res.pair -= below.pair;
+        res.exact_pair -= below.exact_pair;
+    }
+    res
+}
+
+// What matters about a prefix when adding more digits: whether the number
+// has started (leading zeros don't count), the last digit, how long its run
+// is so far (3 means 3 or more), and whether an earlier run was a pair or
+// exactly a pair.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Prefix {
+    started: bool,
+    last: u8,
+    run: u8,
+    pair: bool,
+    exact_pair: bool,
+}
+
+impl Prefix {
+    // Nothing but leading zeros so far.
+    const EMPTY: Prefix = Prefix {
+        started: false,
+        last: 0,
+        run: 0,
+        pair: false,
+        exact_pair: false,
+    };
+
+    // Only called with digits that keep the prefix non-decreasing.
+    fn push(self, digit: u8) -> Prefix {
+        if !self.started {
+            if digit == 0 {
+                self
+            } else {
+                Prefix {
+                    started: true,
+                    last: digit,
+                    run: 1,
+                    ..self
+                }
+            }
+        } else if digit == self.last {
+            Prefix {
+                run: (self.run + 1).min(3),
+                ..self
+            }
+        } else {
+            Prefix {
+                started: true,
+                last: digit,
+                run: 1,
+                pair: self.pair || self.run >= 2,
+                exact_pair: self.exact_pair || self.run == 2,
+            }
+        }
+    }
+
+    fn add_to(self, counts: &mut Counts, n: u64) {
+        counts.non_decreasing += n;
+        if self.pair || self.run >= 2 {
+            counts.pair += n;
+        }
+        if self.exact_pair || self.run == 2 {
+            counts.exact_pair += n;
+        }
+    }
+}
+
+// Counts 0 through `max` in one pass over its digits, with shorter numbers
+// padded out with leading zeros. Prefixes that are already below max are
+// grouped by their state, so each digit costs the same no matter how long
+// the number is. The prefix that matches max exactly is tracked by itself.
+// All zeros is the number 0, which counts as non-decreasing.
+fn count_upto(max: u64) -> Counts {
+    let mut free: HashMap<Prefix, u64> = HashMap::new();
+    let mut tight = Some(Prefix::EMPTY);
+    for limit in digits(max) {
+        let mut next: HashMap<Prefix, u64> = HashMap::new();
+        for (prefix, n) in free {
+            for digit in prefix.last..=9 {
+                *next.entry(prefix.push(digit)).or_insert(0) += n;
+            }
+        }
+        tight = tight.and_then(|prefix| {
+            for digit in prefix.last..limit {
+                *next.entry(prefix.push(digit)).or_insert(0) += 1;
+            }
+            if limit >= prefix.last {
+                Some(prefix.push(limit))
+            } else {
+                None
+            }
+        });
+        free = next;
+    }
+
+    let mut res = Counts::default();
+    for (prefix, n) in free {
+        prefix.add_to(&mut res, n);
+    }
+    if let Some(prefix) = tight {
+        prefix.add_to(&mut res, 1);
+    }
+    res
+}
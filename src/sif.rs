@@ -0,0 +1,304 @@
+///////
+// SIF
+//
+// Space Image Format. An image is a string of digits that make up layers of
+// width x height pixels, one layer after another, each in row order. The
+// first layer is on top, and lower layers show through wherever it's
+// transparent.
+//
+// The digits can come after a header line like "25x6" that gives the size.
+
+pub mod export;
+pub mod png;
+
+use crate::grid::DenseGrid;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_image() {
+        let image = Image::parse("123456789012", 3, 2).unwrap();
+        assert_eq!(2, image.layer_count());
+        assert_eq!(
+            vec![&[1, 2, 3, 4, 5, 6][..], &[7, 8, 9, 0, 1, 2][..]],
+            image.layers().collect::<Vec<_>>()
+        );
+        assert_eq!(Some(&[7, 8, 9, 0, 1, 2][..]), image.layer(1));
+        assert_eq!(None, image.layer(2));
+        assert_eq!(
+            vec![
+                [0, 1, 1, 1, 1, 1, 1, 0, 0, 0],
+                [1, 1, 1, 0, 0, 0, 0, 1, 1, 1],
+            ],
+            image.counts()
+        );
+        assert_eq!(1, image.checksum());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Err("bad digit 'a' at position 3".to_string()),
+            Image::parse("12a4", 2, 1)
+        );
+        assert_eq!(
+            Err("5 pixels isn't a whole number of 2x2 layers (4 pixels each)".to_string()),
+            Image::parse("12345", 2, 2)
+        );
+        assert_eq!(
+            Err("0 pixels isn't a whole number of 2x2 layers (4 pixels each)".to_string()),
+            Image::parse("", 2, 2)
+        );
+        assert_eq!(
+            Err("dimensions can't be 0".to_string()),
+            Image::parse("1234", 0, 2)
+        );
+        assert_eq!(
+            Err("bad pixel 42 at position 2: expected a digit".to_string()),
+            Image::new(2, 1, vec![1, 42])
+        );
+        assert_eq!(
+            Err("bad pixel 10 at position 1: expected a digit".to_string()),
+            Image::new(1, 1, vec![10])
+        );
+        assert_eq!(0, Image::new(1, 1, vec![9]).unwrap().checksum());
+        assert_eq!(Err("dimensions can't be 0".to_string()), parse_size("0x3"));
+        assert_eq!(
+            Err("bad size '25': expected WIDTHxHEIGHT".to_string()),
+            parse_size("25")
+        );
+        assert_eq!(
+            Err("bad dimension 'a': invalid digit found in string".to_string()),
+            parse_size("ax6")
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let image = decode("3x2\n123456789012\n", None).unwrap();
+        assert_eq!(
+            (3, 2, 2),
+            (image.width(), image.height(), image.layer_count())
+        );
+        assert_eq!(image, decode(" 3X2 \n123456789012", Some((3, 2))).unwrap());
+        assert_eq!(image, decode("123456789012", Some((3, 2))).unwrap());
+        assert_eq!(
+            Err("the header says 3x2, not 2x3".to_string()),
+            decode("3x2\n123456789012", Some((2, 3)))
+        );
+        assert_eq!(
+            Err("0 pixels isn't a whole number of 3x2 layers (6 pixels each)".to_string()),
+            decode("3x2", None)
+        );
+        let big = decode(&"0".repeat(300), None).unwrap();
+        assert_eq!((25, 6, 2), (big.width(), big.height(), big.layer_count()));
+    }
+
+    #[test]
+    fn test_render() {
+        let image = Image::parse("0222112222120000", 2, 2).unwrap();
+        assert_eq!(
+            DenseGrid::from_rows(vec![vec![0, 1], vec![1, 0]]),
+            image.flatten()
+        );
+        assert_eq!(" ■\n■ \n", image.render());
+        let clear = Image::parse("2221", 2, 1).unwrap();
+        assert_eq!("?■\n", clear.render());
+    }
+
+    #[test]
+    fn test_day8_input() {
+        let text = include_str!("../data/day8/input");
+        let image = decode(text, None).unwrap();
+        assert_eq!(100, image.layer_count());
+        assert_eq!(2562, image.checksum());
+        // Rows end with spaces, which are easy to lose, so compare without them.
+        let picture = image.render();
+        let picture: Vec<&str> = picture.lines().map(|l| l.trim_end()).collect();
+        assert_eq!(
+            vec![
+                "■■■■ ■■■■ ■    ■■■  ■   ■",
+                "   ■ ■    ■    ■  ■ ■   ■",
+                "  ■  ■■■  ■    ■■■   ■ ■",
+                " ■   ■    ■    ■  ■   ■",
+                "■    ■    ■    ■  ■   ■",
+                "■■■■ ■    ■■■■ ■■■    ■",
+            ],
+            picture
+        );
+    }
+}
+
+pub const BLACK: u8 = 0;
+pub const WHITE: u8 = 1;
+pub const TRANSPARENT: u8 = 2;
+
+// The size of the images in the puzzle.
+pub const PUZZLE_SIZE: (usize, usize) = (25, 6);
+
+// All of the layers are in one buffer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+// How many of each digit a layer has.
+pub type DigitCounts = [usize; 10];
+
+// Parses "WIDTHxHEIGHT".
+pub fn parse_size(text: &str) -> Result<(usize, usize), String> {
+    let parts: Vec<&str> = text.trim().split(['x', 'X']).collect();
+    if parts.len() != 2 {
+        return Err(format!("bad size '{}': expected WIDTHxHEIGHT", text.trim()));
+    }
+    let width = parse_dimension(parts[0])?;
+    let height = parse_dimension(parts[1])?;
+    Ok((width, height))
+}
+
+pub fn parse_dimension(text: &str) -> Result<usize, String> {
+    match text.trim().parse() {
+        Ok(0) => Err("dimensions can't be 0".to_string()),
+        Ok(n) => Ok(n),
+        Err(e) => Err(format!("bad dimension '{}': {}", text.trim(), e)),
+    }
+}
+
+// Reads an image, with or without a header. `size` has to match the header
+// if there is one. Without either, the image is the puzzle's size.
+pub fn decode(text: &str, size: Option<(usize, usize)>) -> Result<Image, String> {
+    let text = text.trim();
+    let (header, digits) = match text.find('\n') {
+        Some(i) if text[..i].contains(['x', 'X']) => (Some(parse_size(&text[..i])?), &text[i..]),
+        _ if text.contains(['x', 'X']) => (Some(parse_size(text)?), ""),
+        _ => (None, text),
+    };
+    let size = match (header, size) {
+        (Some(header), Some(size)) if header != size => {
+            return Err(format!(
+                "the header says {}x{}, not {}x{}",
+                header.0, header.1, size.0, size.1
+            ))
+        }
+        (Some(size), _) | (None, Some(size)) => size,
+        (None, None) => PUZZLE_SIZE,
+    };
+    Image::parse(digits.trim(), size.0, size.1)
+}
+
+impl Image {
+    // The number of pixels has to be a whole number of layers, and there has
+    // to be at least one layer. Pixels are digits, so they go up to 9.
+    pub fn new(width: usize, height: usize, pixels: Vec<u8>) -> Result<Image, String> {
+        if width == 0 || height == 0 {
+            return Err("dimensions can't be 0".to_string());
+        }
+        let size = match width.checked_mul(height) {
+            Some(size) => size,
+            None => return Err(format!("{}x{} is too big", width, height)),
+        };
+        if pixels.is_empty() || !pixels.len().is_multiple_of(size) {
+            return Err(format!(
+                "{} pixels isn't a whole number of {}x{} layers ({} pixels each)",
+                pixels.len(),
+                width,
+                height,
+                size
+            ));
+        }
+        if let Some(i) = pixels.iter().position(|pixel| *pixel > 9) {
+            return Err(format!(
+                "bad pixel {} at position {}: expected a digit",
+                pixels[i],
+                i + 1
+            ));
+        }
+        Ok(Image {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn parse(digits: &str, width: usize, height: usize) -> Result<Image, String> {
+        let pixels = digits
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match c.to_digit(10) {
+                Some(d) => Ok(d as u8),
+                None => Err(format!("bad digit '{}' at position {}", c, i + 1)),
+            })
+            .collect::<Result<Vec<u8>, String>>()?;
+        Image::new(width, height, pixels)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn layer_count(&self) -> usize {
+        self.pixels.len() / (self.width * self.height)
+    }
+
+    // Each layer's pixels, in row order.
+    pub fn layers(&self) -> impl Iterator<Item = &[u8]> {
+        self.pixels.chunks(self.width * self.height)
+    }
+
+    pub fn layer(&self, i: usize) -> Option<&[u8]> {
+        self.layers().nth(i)
+    }
+
+    pub fn counts(&self) -> Vec<DigitCounts> {
+        self.layers()
+            .map(|layer| {
+                let mut counts = [0; 10];
+                for pixel in layer {
+                    counts[*pixel as usize] += 1;
+                }
+                counts
+            })
+            .collect()
+    }
+
+    // On the layer with the fewest 0s, the number of 1s times the number of
+    // 2s. The first layer wins a tie.
+    pub fn checksum(&self) -> usize {
+        let counts = self.counts();
+        let best = counts.iter().min_by_key(|counts| counts[0]).unwrap();
+        best[1] * best[2]
+    }
+
+    // Each pixel is the top one that isn't transparent. It's only
+    // transparent if it's transparent on every layer.
+    pub fn flatten(&self) -> DenseGrid<u8> {
+        let size = self.width * self.height;
+        let mut res = vec![TRANSPARENT; size];
+        for layer in self.layers() {
+            for (pixel, value) in res.iter_mut().zip(layer) {
+                if *pixel == TRANSPARENT {
+                    *pixel = *value;
+                }
+            }
+        }
+        DenseGrid::from_rows(res.chunks(self.width).map(|row| row.to_vec()).collect())
+    }
+
+    // This looks right in a terminal with a dark background. Transparent
+    // pixels are '?'.
+    pub fn render(&self) -> String {
+        self.flatten().render(|_, pixel| match *pixel {
+            BLACK => ' ',
+            WHITE => '■',
+            _ => '?',
+        })
+    }
+}
@@ -0,0 +1,309 @@
+//////////
+// EXPORT
+//
+// Decoded images as picture files: PBM, PGM and PPM (the binary netpbm
+// formats), and PNG. Each pixel becomes a square of `scale` x `scale`
+// pixels in its digit's color from the palette.
+
+use super::{png, Image, BLACK, TRANSPARENT, WHITE};
+use std::path::Path;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Black, white, then a pixel that's transparent on every layer.
+    fn example() -> Image {
+        Image::parse("012222222", 3, 1).unwrap()
+    }
+
+    #[test]
+    fn test_palette() {
+        let palette = Palette::parse("0=102030, 1=#FFFFFF80,,").unwrap();
+        assert_eq!([0x10, 0x20, 0x30, 255], palette.colors[0]);
+        assert_eq!([255, 255, 255, 0x80], palette.colors[1]);
+        assert_eq!(Palette::default().colors[2], palette.colors[2]);
+        assert_eq!(Ok(Palette::default()), Palette::parse(""));
+
+        assert_eq!(
+            Err("bad palette entry '0': expected DIGIT=COLOR".to_string()),
+            Palette::parse("0")
+        );
+        assert_eq!(
+            Err("bad palette entry '10=000000': '10' isn't a digit".to_string()),
+            Palette::parse("10=000000")
+        );
+        assert_eq!(
+            Err("bad palette entry '3=fff': 'fff' isn't RRGGBB or RRGGBBAA".to_string()),
+            Palette::parse("3=fff")
+        );
+        assert_eq!(
+            Err("bad palette entry '3=gggggg': 'gggggg' isn't RRGGBB or RRGGBBAA".to_string()),
+            Palette::parse("3=gggggg")
+        );
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(Some(Format::Png), Format::from_path("out/picture.PNG"));
+        assert_eq!(Some(Format::Pbm), Format::from_path("a.pbm"));
+        assert_eq!(None, Format::from_path("a.gif"));
+        assert_eq!(None, Format::from_path("png"));
+    }
+
+    #[test]
+    fn test_netpbm() {
+        let image = example();
+        let style = Style::default();
+        assert_eq!(
+            Ok(b"P4\n3 1\n\x80".to_vec()),
+            export(&image, Format::Pbm, &style)
+        );
+        assert_eq!(
+            Ok(b"P5\n3 1\n255\n\x00\xff\x80".to_vec()),
+            export(&image, Format::Pgm, &style)
+        );
+        assert_eq!(
+            Ok(b"P6\n3 1\n255\n\x00\x00\x00\xff\xff\xff\x80\x80\x80".to_vec()),
+            export(&image, Format::Ppm, &style)
+        );
+    }
+
+    #[test]
+    fn test_scale() {
+        let style = Style {
+            scale: 3,
+            palette: Palette::parse("1=ff0000").unwrap(),
+        };
+        let (width, height, pixels) = raster(&example(), &style).unwrap();
+        assert_eq!((9, 3), (width, height));
+        for row in pixels.chunks(width) {
+            assert_eq!([0, 0, 0, 255], row[2]);
+            assert_eq!([255, 0, 0, 255], row[3]);
+            assert_eq!([128, 128, 128, 0], row[8]);
+        }
+
+        // Twelve pixels wide needs two bytes for each row. Red is dark enough
+        // to be black.
+        let pbm = export(&example(), Format::Pbm, &Style { scale: 4, ..style }).unwrap();
+        assert_eq!(b"P4\n12 4\n".len() + 4 * 2, pbm.len());
+        assert_eq!(&[0xff, 0x00], &pbm[pbm.len() - 2..]);
+    }
+
+    #[test]
+    fn test_png() {
+        let style = Style {
+            scale: 2,
+            ..Style::default()
+        };
+        let (width, height, pixels) = raster(&example(), &style).unwrap();
+        assert_eq!(
+            png::encode(width, height, &pixels),
+            export(&example(), Format::Png, &style)
+        );
+    }
+
+    #[test]
+    fn test_too_big() {
+        let big = |scale| {
+            export(
+                &example(),
+                Format::Pgm,
+                &Style {
+                    scale,
+                    ..Style::default()
+                },
+            )
+        };
+        let err = |scale| {
+            Err(format!(
+                "3x1 at scale {} is too big (the most is 2147483647 on a side and 268435456 pixels)",
+                scale
+            ))
+        };
+        // 3 << 30 is too wide, 3 << 40 pixels is too many, and the others
+        // overflow.
+        assert_eq!(err(1 << 30), big(1 << 30));
+        assert_eq!(err(1 << 20), big(1 << 20));
+        assert_eq!(err(usize::MAX), big(usize::MAX));
+        assert_eq!(err(usize::MAX / 2), big(usize::MAX / 2));
+        assert!(big(1000).is_ok());
+    }
+}
+
+// Red, green, blue and alpha.
+pub type Rgba = [u8; 4];
+
+// A gigabyte of RGBA, which is as big as a picture gets.
+pub const MAX_PIXELS: usize = 1 << 28;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Pbm,
+    Pgm,
+    Ppm,
+    Png,
+}
+
+// A color for each digit. Only PNG keeps the alpha channel, so pixels that
+// are transparent on every layer are see-through in a PNG and gray in the
+// other formats. Digits other than 0, 1 and 2 are magenta, so they stand out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette {
+    pub colors: [Rgba; 10],
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Style {
+    // 0 works like 1.
+    pub scale: usize,
+    pub palette: Palette,
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        let mut colors = [[255, 0, 255, 255]; 10];
+        colors[BLACK as usize] = [0, 0, 0, 255];
+        colors[WHITE as usize] = [255, 255, 255, 255];
+        colors[TRANSPARENT as usize] = [128, 128, 128, 0];
+        Palette { colors }
+    }
+}
+
+impl Palette {
+    // Changes some of the default colors, with something like
+    // "0=102030,1=#ffffff,2=00000000". Colors are RRGGBB, or RRGGBBAA to set
+    // the alpha too.
+    pub fn parse(spec: &str) -> Result<Palette, String> {
+        let mut res = Palette::default();
+        for entry in spec.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let (digit, color) = match entry.find('=') {
+                Some(i) => (&entry[..i], &entry[i + 1..]),
+                None => {
+                    return Err(format!(
+                        "bad palette entry '{}': expected DIGIT=COLOR",
+                        entry
+                    ))
+                }
+            };
+            let digit = match digit.trim().parse::<usize>() {
+                Ok(d) if d < 10 => d,
+                _ => {
+                    return Err(format!(
+                        "bad palette entry '{}': '{}' isn't a digit",
+                        entry, digit
+                    ))
+                }
+            };
+            res.colors[digit] =
+                parse_color(color).map_err(|e| format!("bad palette entry '{}': {}", entry, e))?;
+        }
+        Ok(res)
+    }
+}
+
+fn parse_color(text: &str) -> Result<Rgba, String> {
+    let hex = text.trim().trim_start_matches('#');
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("'{}' isn't RRGGBB or RRGGBBAA", text.trim()));
+    }
+    let mut res = [0, 0, 0, 255];
+    for (i, byte) in res.iter_mut().enumerate().take(hex.len() / 2) {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+    }
+    Ok(res)
+}
+
+impl Format {
+    // Guesses from a file's extension.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Format> {
+        let ext = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "pbm" => Some(Format::Pbm),
+            "pgm" => Some(Format::Pgm),
+            "ppm" => Some(Format::Ppm),
+            "png" => Some(Format::Png),
+            _ => None,
+        }
+    }
+}
+
+// Fails if the scaled-up picture is more than png::MAX_SIZE on a side or
+// MAX_PIXELS in all, for every format.
+pub fn export(image: &Image, format: Format, style: &Style) -> Result<Vec<u8>, String> {
+    let (width, height, pixels) = raster(image, style)?;
+    let netpbm = |magic: &str, maxval: &str| format!("{}\n{} {}\n{}", magic, width, height, maxval);
+    match format {
+        // 1 is black, and each row is padded out to a whole byte.
+        Format::Pbm => {
+            let mut res = netpbm("P4", "").into_bytes();
+            for row in pixels.chunks(width) {
+                for bits in row.chunks(8) {
+                    let mut byte = 0;
+                    for (i, pixel) in bits.iter().enumerate() {
+                        if luma(*pixel) < 128 {
+                            byte |= 0x80 >> i;
+                        }
+                    }
+                    res.push(byte);
+                }
+            }
+            Ok(res)
+        }
+        Format::Pgm => {
+            let mut res = netpbm("P5", "255\n").into_bytes();
+            res.extend(pixels.iter().map(|pixel| luma(*pixel)));
+            Ok(res)
+        }
+        Format::Ppm => {
+            let mut res = netpbm("P6", "255\n").into_bytes();
+            for pixel in &pixels {
+                res.extend(&pixel[..3]);
+            }
+            Ok(res)
+        }
+        Format::Png => png::encode(width, height, &pixels),
+    }
+}
+
+// The flattened image in color, scaled up.
+fn raster(image: &Image, style: &Style) -> Result<(usize, usize, Vec<Rgba>), String> {
+    let scale = style.scale.max(1);
+    let flat = image.flatten();
+    let too_big = || {
+        format!(
+            "{}x{} at scale {} is too big (the most is {} on a side and {} pixels)",
+            flat.width(),
+            flat.height(),
+            scale,
+            png::MAX_SIZE,
+            MAX_PIXELS
+        )
+    };
+    let width = flat.width().checked_mul(scale).ok_or_else(too_big)?;
+    let height = flat.height().checked_mul(scale).ok_or_else(too_big)?;
+    if width > png::MAX_SIZE || height > png::MAX_SIZE {
+        return Err(too_big());
+    }
+    let count = width.checked_mul(height).ok_or_else(too_big)?;
+    if count > MAX_PIXELS {
+        return Err(too_big());
+    }
+    let mut res = Vec::with_capacity(count);
+    for row in flat.rows() {
+        let mut line = Vec::with_capacity(width);
+        for digit in row {
+            let color = style.palette.colors[*digit as usize];
+            line.extend(std::iter::repeat_n(color, scale));
+        }
+        for _ in 0..scale {
+            res.extend(&line);
+        }
+    }
+    Ok((width, height, res))
+}
+
+fn luma(pixel: Rgba) -> u8 {
+    let [r, g, b, _] = pixel;
+    ((299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000) as u8
+}
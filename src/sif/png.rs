@@ -0,0 +1,226 @@
+///////
+// PNG
+//
+// Just enough PNG to write 8-bit RGBA images without any dependencies. The
+// pixels aren't compressed. They go in "stored" deflate blocks, which every
+// zlib reader understands.
+
+use std::convert::TryFrom;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Splits a PNG into its chunks, checking each CRC along the way.
+    fn chunks(png: &[u8]) -> Vec<(String, Vec<u8>)> {
+        assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+        let mut res = vec![];
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+            let body = &rest[4..8 + len];
+            let crc = &rest[8 + len..12 + len];
+            assert_eq!(&crc32(body).to_be_bytes(), crc);
+            res.push((
+                String::from_utf8(body[..4].to_vec()).unwrap(),
+                body[4..].to_vec(),
+            ));
+            rest = &rest[12 + len..];
+        }
+        res
+    }
+
+    // Undoes zlib_stored, checking the block headers and the Adler-32.
+    fn unstore(data: &[u8]) -> Vec<u8> {
+        assert_eq!(&[0x78, 0x01], &data[..2]);
+        let mut res = vec![];
+        let mut rest = &data[2..];
+        loop {
+            let last = rest[0] == 1;
+            let len = u16::from_le_bytes([rest[1], rest[2]]);
+            assert_eq!(!len, u16::from_le_bytes([rest[3], rest[4]]));
+            res.extend(&rest[5..5 + len as usize]);
+            rest = &rest[5 + len as usize..];
+            if last {
+                break;
+            }
+        }
+        assert_eq!(&adler32(&res).to_be_bytes(), rest);
+        res
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(0xcbf4_3926, crc32(b"123456789"));
+        assert_eq!(0, crc32(b""));
+        assert_eq!(0x11e6_0398, adler32(b"Wikipedia"));
+        assert_eq!(1, adler32(b""));
+    }
+
+    #[test]
+    fn test_encode() {
+        let pixels = [[255, 0, 0, 255], [0, 0, 0, 0]];
+        let png = encode(2, 1, &pixels).unwrap();
+        let chunks = chunks(&png);
+        let kinds: Vec<&str> = chunks.iter().map(|(kind, _)| kind.as_str()).collect();
+        assert_eq!(vec!["IHDR", "IDAT", "IEND"], kinds);
+        assert_eq!(vec![0, 0, 0, 2, 0, 0, 0, 1, 8, 6, 0, 0, 0], chunks[0].1);
+        assert_eq!(vec![0, 255, 0, 0, 255, 0, 0, 0, 0], unstore(&chunks[1].1));
+        assert!(chunks[2].1.is_empty());
+
+        assert_eq!(
+            Err("2 pixels don't make a 2x2 picture".to_string()),
+            encode(2, 2, &pixels)
+        );
+        assert_eq!(
+            Err("2147483648x0 is too big for a PNG (the most is 2147483647 on a side)".to_string()),
+            encode(1 << 31, 0, &[])
+        );
+    }
+
+    #[test]
+    fn test_big_image() {
+        // More than one stored block's worth of pixels.
+        let (width, height) = (200, 100);
+        let pixels: Vec<[u8; 4]> = (0..width * height)
+            .map(|i| [i as u8, (i / 256) as u8, 7, 255])
+            .collect();
+        let chunks = chunks(&encode(width, height, &pixels).unwrap());
+        // The pixels are too big for one IDAT.
+        let idat: Vec<&[u8]> = chunks
+            .iter()
+            .filter(|(kind, _)| kind == "IDAT")
+            .map(|(_, data)| &data[..])
+            .collect();
+        assert_eq!(2, idat.len());
+        assert_eq!(IDAT_SIZE, idat[0].len());
+        let raw = unstore(&idat.concat());
+        assert_eq!(height * (1 + width * 4), raw.len());
+        assert!(raw.len() > MAX_BLOCK);
+        let row = &raw[3 * (1 + width * 4)..4 * (1 + width * 4)];
+        assert_eq!(0, row[0]);
+        assert_eq!(&pixels[3 * width][..], &row[1..5]);
+
+        assert_eq!(
+            vec![0x78, 0x01, 1, 0, 0, 0xff, 0xff, 0, 0, 0, 1],
+            zlib_stored(&[])
+        );
+    }
+}
+
+// The most that a stored block can hold.
+const MAX_BLOCK: usize = 65535;
+
+// PNG sizes are 31 bits.
+pub const MAX_SIZE: usize = i32::MAX as usize;
+
+// Chunk lengths are 31 bits too, but the pixels get split up into much
+// smaller IDAT chunks, the way most encoders do it.
+const MAX_CHUNK: usize = i32::MAX as usize;
+const IDAT_SIZE: usize = 1 << 16;
+
+// `pixels` are [red, green, blue, alpha], in row order.
+pub fn encode(width: usize, height: usize, pixels: &[[u8; 4]]) -> Result<Vec<u8>, String> {
+    if width > MAX_SIZE || height > MAX_SIZE {
+        return Err(format!(
+            "{}x{} is too big for a PNG (the most is {} on a side)",
+            width, height, MAX_SIZE
+        ));
+    }
+    if width.checked_mul(height) != Some(pixels.len()) {
+        return Err(format!(
+            "{} pixels don't make a {}x{} picture",
+            pixels.len(),
+            width,
+            height
+        ));
+    }
+    let mut res = b"\x89PNG\r\n\x1a\n".to_vec();
+
+    let mut header = vec![];
+    header.extend(&(width as u32).to_be_bytes());
+    header.extend(&(height as u32).to_be_bytes());
+    // 8 bits per channel, RGBA, deflate, the usual filters, no interlacing.
+    header.extend(&[8, 6, 0, 0, 0]);
+    chunk(&mut res, b"IHDR", &header)?;
+
+    // Each row starts with its filter, which is always 0 (none).
+    let mut raw = Vec::with_capacity(pixels.len() * 4 + height);
+    for row in pixels.chunks(width.max(1)) {
+        raw.push(0);
+        for pixel in row {
+            raw.extend(pixel);
+        }
+    }
+    for data in zlib_stored(&raw).chunks(IDAT_SIZE) {
+        chunk(&mut res, b"IDAT", data)?;
+    }
+    chunk(&mut res, b"IEND", &[])?;
+    Ok(res)
+}
+
+// A chunk is its length, type, data, and a CRC of the type and data.
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) -> Result<(), String> {
+    let len = match u32::try_from(data.len()) {
+        Ok(len) if data.len() <= MAX_CHUNK => len,
+        _ => {
+            return Err(format!(
+                "{} bytes is too long for a PNG chunk (the most is {})",
+                data.len(),
+                MAX_CHUNK
+            ))
+        }
+    };
+    out.extend(&len.to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(&crc.to_be_bytes());
+    Ok(())
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window, and a check value that makes the first two
+    // bytes a multiple of 31.
+    let mut res = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if data.is_empty() {
+        res.extend(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        // The low bit marks the last block. The next two bits are 0 for a
+        // stored block.
+        res.push(if blocks.peek().is_none() { 1 } else { 0 });
+        let len = block.len() as u16;
+        res.extend(&len.to_le_bytes());
+        res.extend(&(!len).to_le_bytes());
+        res.extend(block);
+    }
+    res.extend(&adler32(data).to_be_bytes());
+    res
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
@@ -0,0 +1,396 @@
+/////////
+// WIRES
+//
+// Wires that run from the origin along horizontal and vertical segments.
+// Crossings are found by intersecting the segments with each other instead
+// of visiting every cell, so long segments don't cost anything extra.
+
+pub mod render;
+
+use crate::grid::{Direction, Point};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wire(path: &str) -> Wire {
+        Wire::new(&parse_path(path).unwrap())
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            Ok(vec![
+                (Direction::Right, 8),
+                (Direction::Up, 5),
+                (Direction::Left, 5),
+                (Direction::Down, 3)
+            ]),
+            parse_path(" r8, U 5 ,l5,\tD3\n")
+        );
+
+        let err = |segment, text: &str, message: &str| {
+            Err(PathError {
+                segment,
+                text: text.to_string(),
+                message: message.to_string(),
+            })
+        };
+        assert_eq!(err(0, " \n", "empty path"), parse_path(" \n"));
+        assert_eq!(err(2, "", "missing segment"), parse_path("R1,,U2"));
+        assert_eq!(err(3, "", "missing segment"), parse_path("R1,U2,"));
+        assert_eq!(err(1, "R", "missing distance"), parse_path("R"));
+        assert_eq!(
+            err(2, "N4", "unrecognized direction 'N'"),
+            parse_path("R1,N4")
+        );
+        assert_eq!(
+            err(1, "U-3", "bad distance '-3': invalid digit found in string"),
+            parse_path("U-3")
+        );
+        assert_eq!(
+            "segment 2 ('x1'): unrecognized direction 'x'",
+            parse_path("U1, x1").unwrap_err().to_string()
+        );
+        assert_eq!("empty path", parse_path("").unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_examples() {
+        let tests = vec![
+            ("R8,U5,L5,D3", "U7,R6,D4,L4", 6, 30),
+            (
+                "R75,D30,R83,U83,L12,D49,R71,U7,L72",
+                "U62,R66,U55,R34,D71,R55,D58,R83",
+                159,
+                610,
+            ),
+            (
+                "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+                "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+                135,
+                410,
+            ),
+        ];
+        for (a, b, dist, steps) in tests {
+            let crossings = crossings(&[wire(a), wire(b)]);
+            assert_eq!(Some(dist), closest(&crossings, 2), "{} / {}", a, b);
+            assert_eq!(Some(steps), shortest(&crossings, 2), "{} / {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_crossing_details() {
+        let crossings = crossings(&[wire("R8,U5,L5,D3"), wire("U7,R6,D4,L4")]);
+        assert_eq!(
+            vec![
+                Crossing {
+                    point: Point::new(3, -3),
+                    distance: 6,
+                    steps: vec![(0, 20), (1, 20)].into_iter().collect(),
+                },
+                Crossing {
+                    point: Point::new(6, -5),
+                    distance: 11,
+                    steps: vec![(0, 15), (1, 15)].into_iter().collect(),
+                },
+            ],
+            crossings
+        );
+    }
+
+    #[test]
+    fn test_overlapping_segments() {
+        // Both run along the same line for a while, and the second one comes
+        // back through the origin.
+        let a = wire("R10");
+        let b = wire("U2,R3,D2,R4,L9");
+        let points: Vec<Point> = a.crossing_points(&b).into_iter().collect();
+        assert_eq!(
+            vec![
+                Point::new(1, 0),
+                Point::new(2, 0),
+                Point::new(3, 0),
+                Point::new(4, 0),
+                Point::new(5, 0),
+                Point::new(6, 0),
+                Point::new(7, 0),
+            ],
+            points
+        );
+        assert_eq!(Some(7), b.steps_to(Point::new(3, 0)));
+        assert_eq!(Some(17), b.steps_to(Point::new(1, 0)));
+        assert_eq!(None, b.steps_to(Point::new(8, 0)));
+        assert_eq!(20, b.length());
+        assert_eq!(Point::new(-2, 0), b.end());
+    }
+
+    #[test]
+    fn test_more_than_two_wires() {
+        let crossings = crossings(&[wire("R5"), wire("U1,R2,D2"), wire("D1,R4,U2")]);
+        let found: Vec<(Point, Vec<usize>)> =
+            crossings.iter().map(|c| (c.point, c.wires())).collect();
+        assert_eq!(
+            vec![
+                (Point::new(2, 0), vec![0, 1]),
+                (Point::new(2, 1), vec![1, 2]),
+                (Point::new(4, 0), vec![0, 2]),
+            ],
+            found
+        );
+        assert_eq!(Some(2), closest(&crossings, 2));
+        assert_eq!(Some(6), shortest(&crossings, 2));
+        assert_eq!(None, closest(&crossings, 3));
+    }
+
+    #[test]
+    fn test_k_wire_crossings() {
+        // All three go through (2, 0). The first two also meet at (1, 0).
+        let wires = [wire("R3"), wire("R1,U1,R1,D2"), wire("U1,R2,D1")];
+        let crossings = crossings(&wires);
+        let three = crossings.iter().find(|c| c.steps.len() == 3).unwrap();
+        assert_eq!(Point::new(2, 0), three.point);
+        assert_eq!(vec![0, 1, 2], three.wires());
+        assert_eq!(
+            vec![2, 4, 4],
+            three.steps.values().cloned().collect::<Vec<_>>()
+        );
+        assert_eq!(6, three.fastest_steps(2));
+        assert_eq!(10, three.fastest_steps(3));
+
+        assert_eq!(Some(1), closest(&crossings, 2));
+        assert_eq!(Some(2), shortest(&crossings, 2));
+        assert_eq!(Some(2), closest(&crossings, 3));
+        assert_eq!(Some(10), shortest(&crossings, 3));
+        assert_eq!(None, shortest(&crossings, 4));
+    }
+}
+
+// A wire as it's written down: a direction and a distance for each segment.
+pub type Path = Vec<(Direction, u32)>;
+
+// `segment` counts from 1. It's 0 when there isn't a segment to blame.
+#[derive(Debug, PartialEq)]
+pub struct PathError {
+    pub segment: usize,
+    pub text: String,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+    // How far along the wire `start` is.
+    pub steps: i64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Wire {
+    pub segments: Vec<Segment>,
+}
+
+// A point where two or more wires cross. `steps` has an entry for each wire
+// that goes through the point, with how far along the wire the point first
+// shows up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Crossing {
+    pub point: Point,
+    pub distance: i64,
+    pub steps: BTreeMap<usize, i64>,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.segment == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(
+                f,
+                "segment {} ('{}'): {}",
+                self.segment, self.text, self.message
+            )
+        }
+    }
+}
+
+// Parses something like "R75,D30,U83". Directions can be lowercase, and there
+// can be whitespace around segments and between a direction and its
+// distance.
+pub fn parse_path(text: &str) -> Result<Path, PathError> {
+    if text.trim().is_empty() {
+        return Err(PathError {
+            segment: 0,
+            text: text.to_string(),
+            message: "empty path".to_string(),
+        });
+    }
+    text.split(',')
+        .enumerate()
+        .map(|(i, s)| {
+            parse_segment(s.trim()).map_err(|message| PathError {
+                segment: i + 1,
+                text: s.trim().to_string(),
+                message,
+            })
+        })
+        .collect()
+}
+
+fn parse_segment(s: &str) -> Result<(Direction, u32), String> {
+    let mut chars = s.chars();
+    let dir = match chars.next().map(|c| (c, c.to_ascii_uppercase())) {
+        None => return Err("missing segment".to_string()),
+        Some((_, 'U')) => Direction::Up,
+        Some((_, 'D')) => Direction::Down,
+        Some((_, 'L')) => Direction::Left,
+        Some((_, 'R')) => Direction::Right,
+        Some((c, _)) => return Err(format!("unrecognized direction '{}'", c)),
+    };
+    let distance = chars.as_str().trim_start();
+    if distance.is_empty() {
+        return Err("missing distance".to_string());
+    }
+    distance
+        .parse()
+        .map(|distance| (dir, distance))
+        .map_err(|e| format!("bad distance '{}': {}", distance, e))
+}
+
+impl Segment {
+    pub fn contains(&self, p: Point) -> bool {
+        let (min, max) = self.corners();
+        (min.x..=max.x).contains(&p.x) && (min.y..=max.y).contains(&p.y)
+    }
+
+    // Every point the two segments share. Crossing segments share one point,
+    // but segments running along the same line can share a lot.
+    pub fn overlap(&self, other: &Segment) -> Vec<Point> {
+        let (a_min, a_max) = self.corners();
+        let (b_min, b_max) = other.corners();
+        let min = Point::new(a_min.x.max(b_min.x), a_min.y.max(b_min.y));
+        let max = Point::new(a_max.x.min(b_max.x), a_max.y.min(b_max.y));
+        let mut res = vec![];
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                res.push(Point::new(x, y));
+            }
+        }
+        res
+    }
+
+    fn corners(&self) -> (Point, Point) {
+        (
+            Point::new(self.start.x.min(self.end.x), self.start.y.min(self.end.y)),
+            Point::new(self.start.x.max(self.end.x), self.start.y.max(self.end.y)),
+        )
+    }
+}
+
+impl Wire {
+    pub fn new(path: &[(Direction, u32)]) -> Wire {
+        let mut res = Wire::default();
+        for (dir, count) in path {
+            res.extend(*dir, *count as i64);
+        }
+        res
+    }
+
+    pub fn extend(&mut self, dir: Direction, count: i64) {
+        let start = self.end();
+        self.segments.push(Segment {
+            start,
+            end: start.step_by(dir, count),
+            steps: self.length(),
+        });
+    }
+
+    pub fn end(&self) -> Point {
+        self.segments.last().map_or(Point::ORIGIN, |s| s.end)
+    }
+
+    pub fn length(&self) -> i64 {
+        self.segments
+            .last()
+            .map_or(0, |s| s.steps + s.start.manhattan(s.end))
+    }
+
+    // How far along the wire it first gets to `p`.
+    pub fn steps_to(&self, p: Point) -> Option<i64> {
+        self.segments
+            .iter()
+            .find(|s| s.contains(p))
+            .map(|s| s.steps + s.start.manhattan(p))
+    }
+
+    // Every point that both wires go through, other than the origin.
+    pub fn crossing_points(&self, other: &Wire) -> BTreeSet<Point> {
+        let mut res = BTreeSet::new();
+        for a in &self.segments {
+            for b in &other.segments {
+                res.extend(a.overlap(b));
+            }
+        }
+        res.remove(&Point::ORIGIN);
+        res
+    }
+}
+
+impl Crossing {
+    pub fn wires(&self) -> Vec<usize> {
+        self.steps.keys().cloned().collect()
+    }
+
+    // The total length of the `k` wires that get here soonest.
+    pub fn fastest_steps(&self, k: usize) -> i64 {
+        let mut steps: Vec<i64> = self.steps.values().cloned().collect();
+        steps.sort_unstable();
+        steps.iter().take(k).sum()
+    }
+}
+
+// Every point where wires cross, in order, with all of the wires that go
+// through it.
+pub fn crossings(wires: &[Wire]) -> Vec<Crossing> {
+    let mut found: BTreeMap<Point, BTreeSet<usize>> = BTreeMap::new();
+    for (i, a) in wires.iter().enumerate() {
+        for (j, b) in wires.iter().enumerate().skip(i + 1) {
+            for point in a.crossing_points(b) {
+                let entry = found.entry(point).or_default();
+                entry.insert(i);
+                entry.insert(j);
+            }
+        }
+    }
+    found
+        .into_iter()
+        .map(|(point, on)| Crossing {
+            point,
+            distance: point.manhattan(Point::ORIGIN),
+            steps: on
+                .into_iter()
+                .map(|i| (i, wires[i].steps_to(point).unwrap()))
+                .collect(),
+        })
+        .collect()
+}
+
+// The closest crossing to the origin with at least `k` wires.
+pub fn closest(crossings: &[Crossing], k: usize) -> Option<i64> {
+    crossings
+        .iter()
+        .filter(|c| c.steps.len() >= k)
+        .map(|c| c.distance)
+        .min()
+}
+
+// The least total wire needed for `k` wires to meet somewhere.
+pub fn shortest(crossings: &[Crossing], k: usize) -> Option<i64> {
+    crossings
+        .iter()
+        .filter(|c| c.steps.len() >= k)
+        .map(|c| c.fastest_steps(k))
+        .min()
+}
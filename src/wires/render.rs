@@ -0,0 +1,205 @@
+//////////
+// RENDER
+//
+// Pictures of wires. SVG works for any size. Small layouts can also be drawn
+// as text, like the pictures in the puzzle.
+
+use super::{Crossing, Wire};
+use crate::grid::{Bounds, Point, SparseGrid};
+use std::fmt::Write;
+
+#[cfg(test)]
+mod tests {
+    use super::super::{crossings, parse_path};
+    use super::*;
+
+    fn example() -> (Vec<Wire>, Vec<Crossing>) {
+        let wires: Vec<Wire> = ["R8,U5,L5,D3", "U7,R6,D4,L4"]
+            .iter()
+            .map(|s| Wire::new(&parse_path(s).unwrap()))
+            .collect();
+        let crossings = crossings(&wires);
+        (wires, crossings)
+    }
+
+    #[test]
+    fn test_ascii() {
+        let (wires, crossings) = example();
+        assert_eq!(
+            Some(
+                "\
++-----+..
+|.....|..
+|..+--X-+
+|..|..|.|
+|.-X--+.|
+|..|....|
+|.......|
+o-------+
+"
+                .to_string()
+            ),
+            ascii(&wires, &crossings)
+        );
+
+        let far = Wire::new(&parse_path("R500").unwrap());
+        assert_eq!(None, ascii(&[far], &[]));
+    }
+
+    #[test]
+    fn test_svg() {
+        let (wires, crossings) = example();
+        let svg = svg(&wires, &crossings);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(
+            r##"<polyline points="0,0 8,0 8,-5 3,-5 3,-2" fill="none" stroke="#e41a1c""##
+        ));
+        assert!(svg.contains(
+            r##"<polyline points="0,0 0,-7 6,-7 6,-3 2,-3" fill="none" stroke="#377eb8""##
+        ));
+        assert!(svg.contains(">d=6 w=40</text>"));
+        assert!(svg.contains(">d=11 w=30</text>"));
+        assert_eq!(2, svg.matches("<polyline").count());
+        assert_eq!(3, svg.matches("<circle").count());
+    }
+}
+
+const COLORS: &[&str] = &[
+    "#e41a1c", "#377eb8", "#4daf4a", "#984ea3", "#ff7f00", "#a65628", "#f781bf", "#999999",
+];
+
+// The SVG is this wide, and as tall as it needs to be.
+const SVG_WIDTH: f64 = 800.0;
+
+// Text pictures bigger than this aren't worth looking at.
+pub const MAX_ASCII_WIDTH: usize = 120;
+pub const MAX_ASCII_HEIGHT: usize = 60;
+
+// Each wire is a polyline in its own color. The origin is a black dot, and
+// each crossing gets a dot and a label with its distance from the origin (d)
+// and the total wire leading up to it (w).
+pub fn svg(wires: &[Wire], crossings: &[Crossing]) -> String {
+    let bounds = bounds(wires);
+    let size = bounds.width().max(bounds.height()) as f64;
+    let pad = (size * 0.05).max(1.0);
+    let stroke = (size / 400.0).max(0.1);
+    let font = (size / 60.0).max(0.5);
+    let (x, y) = (bounds.min.x as f64 - pad, bounds.min.y as f64 - pad);
+    let (w, h) = (
+        bounds.width() as f64 + 2.0 * pad,
+        bounds.height() as f64 + 2.0 * pad,
+    );
+
+    let mut res = String::new();
+    writeln!(
+        res,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        SVG_WIDTH,
+        (SVG_WIDTH * h / w).round(),
+        x,
+        y,
+        w,
+        h
+    )
+    .unwrap();
+    writeln!(
+        res,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="white"/>"#,
+        x, y, w, h
+    )
+    .unwrap();
+    for (i, wire) in wires.iter().enumerate() {
+        let mut points = vec![format!("{},{}", 0, 0)];
+        for segment in &wire.segments {
+            points.push(format!("{},{}", segment.end.x, segment.end.y));
+        }
+        writeln!(
+            res,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"><title>wire {}</title></polyline>"#,
+            points.join(" "),
+            COLORS[i % COLORS.len()],
+            stroke,
+            i + 1
+        )
+        .unwrap();
+    }
+    writeln!(
+        res,
+        r#"<circle cx="0" cy="0" r="{}" fill="black"><title>origin</title></circle>"#,
+        stroke * 3.0
+    )
+    .unwrap();
+    for crossing in crossings {
+        let label = format!(
+            "d={} w={}",
+            crossing.distance,
+            crossing.fastest_steps(crossing.steps.len())
+        );
+        let (cx, cy) = (crossing.point.x, crossing.point.y);
+        writeln!(
+            res,
+            r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="black" stroke-width="{}"/>"#,
+            cx,
+            cy,
+            stroke * 2.0,
+            stroke / 2.0
+        )
+        .unwrap();
+        writeln!(
+            res,
+            r#"<text x="{}" y="{}" font-size="{}" font-family="monospace">{}</text>"#,
+            cx as f64 + stroke * 3.0,
+            cy as f64 - stroke * 3.0,
+            font,
+            label
+        )
+        .unwrap();
+    }
+    res.push_str("</svg>\n");
+    res
+}
+
+// '-' and '|' for wires, '+' where a wire turns, 'X' at crossings and 'o' at
+// the origin. Returns None if the picture would be too big.
+pub fn ascii(wires: &[Wire], crossings: &[Crossing]) -> Option<String> {
+    let bounds = bounds(wires);
+    if bounds.width() > MAX_ASCII_WIDTH || bounds.height() > MAX_ASCII_HEIGHT {
+        return None;
+    }
+
+    let mut grid = SparseGrid::new();
+    for wire in wires {
+        for (i, segment) in wire.segments.iter().enumerate() {
+            let c = if segment.start.y == segment.end.y {
+                '-'
+            } else {
+                '|'
+            };
+            for p in Bounds::from_points(vec![segment.start, segment.end])
+                .unwrap()
+                .points()
+            {
+                grid.set(p, c);
+            }
+            if i > 0 {
+                grid.set(segment.start, '+');
+            }
+        }
+    }
+    for crossing in crossings {
+        grid.set(crossing.point, 'X');
+    }
+    grid.set(Point::ORIGIN, 'o');
+    Some(grid.render(|_, c| *c.unwrap_or(&'.')))
+}
+
+fn bounds(wires: &[Wire]) -> Bounds {
+    let mut res = Bounds::from_points(vec![Point::ORIGIN]).unwrap();
+    for wire in wires {
+        for segment in &wire.segments {
+            res.include(segment.end);
+        }
+    }
+    res
+}